@@ -0,0 +1,1035 @@
+use rand::Rng;
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, BitAnd, Div, Mul, Neg, Rem, Shl, Shr, Sub},
+    str::FromStr,
+};
+
+/// Arbitrary-precision unsigned integer.
+///
+/// Stored as little-endian 64-bit limbs with no trailing zero limbs, so zero
+/// is the empty vector.
+///
+/// # Examples
+/// ```
+/// use cpr::bigint::BigUint;
+/// let a: BigUint = "123456789012345678901234567890".parse().unwrap();
+/// let b = BigUint::from(987654321u64);
+/// assert_eq!((&a * &b).to_string(), "121932631124828532112482853211126352690");
+/// assert_eq!((&a / &b).to_string(), "124999998873437499901");
+/// assert_eq!((&a % &b).to_string(), "574845669");
+/// assert_eq!(&(&a - &b) + &b, a);
+/// assert_eq!(BigUint::from_hex("ff00").unwrap(), BigUint::from(0xff00u64));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self { limbs: vec![] }
+    }
+
+    pub fn one() -> Self {
+        Self::from(1u64)
+    }
+
+    fn from_limbs(mut limbs: Vec<u64>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self { limbs }
+    }
+
+    /// Parse big-endian bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::bigint::BigUint;
+    /// let n = BigUint::from_bytes_be(b"\x01\x00\x00\x00\x00\x00\x00\x00\x00");
+    /// assert_eq!(n, BigUint::one() << 64);
+    /// assert_eq!(n.to_bytes_be(), b"\x01\x00\x00\x00\x00\x00\x00\x00\x00");
+    /// assert_eq!(BigUint::zero().to_bytes_be(), b"");
+    /// assert_eq!(BigUint::from(1u64).to_bytes_be_padded(4), b"\x00\x00\x00\x01");
+    /// ```
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .rchunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0u64, |limb, &byte| (limb << 8) | byte as u64)
+            })
+            .collect();
+        Self::from_limbs(limbs)
+    }
+
+    /// Serialize to big-endian bytes without leading zeroes.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let bytes = self
+            .limbs
+            .iter()
+            .rev()
+            .flat_map(|limb| limb.to_be_bytes())
+            .collect::<Vec<u8>>();
+        let skip = bytes.iter().take_while(|&&b| b == 0).count();
+        bytes[skip..].to_vec()
+    }
+
+    /// Serialize to big-endian bytes, left-padded with zeroes to `len` bytes.
+    pub fn to_bytes_be_padded(&self, len: usize) -> Vec<u8> {
+        let bytes = self.to_bytes_be();
+        if bytes.len() >= len {
+            return bytes;
+        }
+        [vec![0; len - bytes.len()], bytes].concat()
+    }
+
+    /// Parse a hex string, ignoring any whitespace.
+    pub fn from_hex(s: &str) -> Option<Self> {
+        let mut s = s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+        if s.len() % 2 != 0 {
+            s.insert(0, '0');
+        }
+        hex::decode(s).ok().map(|bytes| Self::from_bytes_be(&bytes))
+    }
+
+    pub fn to_hex(&self) -> String {
+        format!("{:x}", self)
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.len() {
+            0 => Some(0),
+            1 => Some(self.limbs[0]),
+            _ => None,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.limbs == [1]
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.limbs.first().map_or(true, |limb| limb & 1 == 0)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        !self.is_even()
+    }
+
+    /// Number of significant bits.
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() * 64 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Whether bit `i` is set.
+    pub fn bit(&self, i: usize) -> bool {
+        self.limbs
+            .get(i / 64)
+            .map_or(false, |limb| (limb >> (i % 64)) & 1 == 1)
+    }
+
+    pub fn trailing_zeros(&self) -> usize {
+        match self.limbs.iter().position(|&limb| limb != 0) {
+            Some(i) => i * 64 + self.limbs[i].trailing_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Quotient and remainder. Panics on division by zero.
+    pub fn divmod(&self, other: &Self) -> (Self, Self) {
+        assert!(!other.is_zero(), "division by zero");
+        if self < other {
+            return (Self::zero(), self.clone());
+        }
+        let (q, r) = divmod_limbs(&self.limbs, &other.limbs);
+        (Self::from_limbs(q), Self::from_limbs(r))
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut out = Self::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                out = &out * &base;
+            }
+            base = &base * &base;
+            exp >>= 1;
+        }
+        out
+    }
+
+    /// Modular exponentiation.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::bigint::BigUint;
+    /// // Textbook RSA: p = 61, q = 53, e = 17, d = 2753
+    /// let n = BigUint::from(3233u64);
+    /// let c = BigUint::from(65u64).modpow(&17u64.into(), &n);
+    /// assert_eq!(c, BigUint::from(2790u64));
+    /// assert_eq!(c.modpow(&2753u64.into(), &n), BigUint::from(65u64));
+    /// // Even moduli work too
+    /// let m = BigUint::from(1u64 << 40);
+    /// assert_eq!(BigUint::from(3u64).modpow(&100u64.into(), &m), BigUint::from(368548778961u64));
+    ///
+    /// // 512-bit RSA
+    /// let n = BigUint::from_hex(
+    ///     "8ec5c31897862efadaf8543d25ab7b1c1ede326d07b700ab287e8cbbf7076138\
+    ///      d00e25a772375f322f0508b82882b3a04ba4309dd1a453f904a55d6b7584ebeb",
+    /// ).unwrap();
+    /// let d = BigUint::from_hex(
+    ///     "208281a138e7920d070d62c925a9bd77b2c3afc1c9eeeed50a7c7e79528fb8a0\
+    ///      4b16f5d1440d798ed16ab808745dce9e7ae083dda8b0f7ff3e5363584dc0c3f9",
+    /// ).unwrap();
+    /// let c = BigUint::from_hex(
+    ///     "23f292f13677e4e89ea2006cbd23f35ccc29d99ed34c909f2dfd59eb7dc81529\
+    ///      78ce183fc72d698a7ed80db3d0efacc11214f82f5a336601cb84e23b5f956a17",
+    /// ).unwrap();
+    /// let m = BigUint::from_bytes_be(b"YELLOW SUBMARINE");
+    /// assert_eq!(m.modpow(&65537u64.into(), &n), c);
+    /// assert_eq!(c.modpow(&d, &n).to_bytes_be(), b"YELLOW SUBMARINE");
+    ///
+    /// // Diffie-Hellman over the 1536-bit NIST prime
+    /// let p = BigUint::from_hex(
+    ///     "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74\
+    ///      020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f1437\
+    ///      4fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7ed\
+    ///      ee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf05\
+    ///      98da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb\
+    ///      9ed529077096966d670c354e4abc9804f1746c08ca237327ffffffffffffffff",
+    /// ).unwrap();
+    /// let g = BigUint::from(2u64);
+    /// let a = BigUint::from_bytes_be(b"alice's secret exponent");
+    /// let b = BigUint::from_bytes_be(b"bob's secret exponent");
+    /// let s = g.modpow(&b, &p).modpow(&a, &p);
+    /// assert_eq!(s, g.modpow(&a, &p).modpow(&b, &p));
+    /// assert!(s.to_hex().starts_with("a7dfef392205d855147711c7c39efde71ab8ae2f"));
+    /// assert!(g.modpow(&(&p - 1), &p).is_one());
+    /// ```
+    pub fn modpow(&self, exp: &Self, modulus: &Self) -> Self {
+        assert!(!modulus.is_zero(), "division by zero");
+        if modulus.is_one() {
+            return Self::zero();
+        }
+        if modulus.is_odd() {
+            return Montgomery::new(modulus).pow(self, exp);
+        }
+
+        let base = self % modulus;
+        let mut out = Self::one();
+        for i in (0..exp.bits()).rev() {
+            out = &(&out * &out) % modulus;
+            if exp.bit(i) {
+                out = &(&out * &base) % modulus;
+            }
+        }
+        out
+    }
+
+    /// Greatest common divisor.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::bigint::BigUint;
+    /// let a = BigUint::from(2u64 * 3 * 5 * 7 * 11);
+    /// let b = BigUint::from(3u64 * 7 * 13);
+    /// assert_eq!(a.gcd(&b), BigUint::from(21u64));
+    /// ```
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = b;
+            b = r;
+        }
+        a
+    }
+
+    pub fn lcm(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::zero();
+        }
+        &(self / &self.gcd(other)) * other
+    }
+
+    /// Modular inverse, if it exists.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::bigint::BigUint;
+    /// let d = BigUint::from(17u64).modinv(&3120u64.into()).unwrap();
+    /// assert_eq!(d, BigUint::from(2753u64));
+    /// assert!(BigUint::from(6u64).modinv(&9u64.into()).is_none());
+    /// ```
+    pub fn modinv(&self, modulus: &Self) -> Option<Self> {
+        let (g, x, _) = extended_gcd(&(self % modulus), modulus);
+        if !g.is_one() {
+            return None;
+        }
+        Some(x.modulo(modulus))
+    }
+
+    /// Floor of the integer `n`th root.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::bigint::BigUint;
+    /// let x: BigUint = "123456789123456789123456789".parse().unwrap();
+    /// assert_eq!(x.pow(3).nth_root(3), x);
+    /// assert_eq!((&x.pow(3) - &BigUint::one()).nth_root(3), &x - &BigUint::one());
+    /// assert_eq!(BigUint::from(99u64).nth_root(2), BigUint::from(9u64));
+    /// ```
+    pub fn nth_root(&self, n: u32) -> Self {
+        assert!(n > 0, "zeroth root");
+        if self.is_zero() || n == 1 {
+            return self.clone();
+        }
+
+        // Newton's method, starting from a power of two that's guaranteed to
+        // be too big and walking down until the iterates stop decreasing.
+        let n_big = Self::from(n as u64);
+        let n_minus_one = Self::from(n as u64 - 1);
+        let mut x = Self::one() << ((self.bits() + n as usize - 1) / n as usize);
+        loop {
+            let y = &(&(&n_minus_one * &x) + &(self / &x.pow(n - 1))) / &n_big;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    pub fn sqrt(&self) -> Self {
+        self.nth_root(2)
+    }
+
+    /// Uniformly random integer with at most `bits` bits.
+    pub fn random_bits(bits: usize) -> Self {
+        let mut rng = rand::thread_rng();
+        let mut limbs = (0..(bits + 63) / 64)
+            .map(|_| rng.gen::<u64>())
+            .collect::<Vec<_>>();
+        if bits % 64 != 0 {
+            if let Some(top) = limbs.last_mut() {
+                *top >>= 64 - bits % 64;
+            }
+        }
+        Self::from_limbs(limbs)
+    }
+
+    /// Uniformly random integer in `[0, bound)`.
+    pub fn random_below(bound: &Self) -> Self {
+        assert!(!bound.is_zero(), "empty range");
+        loop {
+            let candidate = Self::random_bits(bound.bits());
+            if &candidate < bound {
+                return candidate;
+            }
+        }
+    }
+
+    /// Uniformly random integer in `[low, high)`.
+    pub fn random_range(low: &Self, high: &Self) -> Self {
+        low + &Self::random_below(&(high - low))
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        Self::from_limbs(vec![n])
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(n: u32) -> Self {
+        Self::from(n as u64)
+    }
+}
+
+impl From<usize> for BigUint {
+    fn from(n: usize) -> Self {
+        Self::from(n as u64)
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(n: u128) -> Self {
+        Self::from_limbs(vec![n as u64, (n >> 64) as u64])
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = String;
+
+    /// Parse a decimal string, or a hex string prefixed with `0x`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(hex) = s.strip_prefix("0x") {
+            return Self::from_hex(hex).ok_or_else(|| format!("invalid hex: {}", s));
+        }
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("invalid decimal: {}", s));
+        }
+        let ten = Self::from(10u64);
+        Ok(s.bytes()
+            .fold(Self::zero(), |n, b| &(&n * &ten) + (b - b'0') as u64))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+        // Peel off 19 decimal digits at a time
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut chunks = vec![];
+        let mut n = self.clone();
+        while !n.is_zero() {
+            let (q, r) = divmod_small(&n.limbs, CHUNK);
+            chunks.push(r);
+            n = Self::from_limbs(q);
+        }
+        let mut s = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            s.push_str(&format!("{:019}", chunk));
+        }
+        f.pad_integral(true, "", &s)
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self.limbs.split_last() {
+            Some((top, rest)) => rest.iter().rev().fold(format!("{:x}", top), |s, limb| {
+                s + &format!("{:016x}", limb)
+            }),
+            None => "0".into(),
+        };
+        f.pad_integral(true, "0x", &s)
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+fn add(a: &BigUint, b: &BigUint) -> BigUint {
+    let (long, short) = if a.limbs.len() >= b.limbs.len() {
+        (&a.limbs, &b.limbs)
+    } else {
+        (&b.limbs, &a.limbs)
+    };
+    let mut out = Vec::with_capacity(long.len() + 1);
+    let mut carry = false;
+    for (i, &x) in long.iter().enumerate() {
+        let (sum, c1) = x.overflowing_add(*short.get(i).unwrap_or(&0));
+        let (sum, c2) = sum.overflowing_add(carry as u64);
+        out.push(sum);
+        carry = c1 || c2;
+    }
+    if carry {
+        out.push(1);
+    }
+    BigUint::from_limbs(out)
+}
+
+fn sub(a: &BigUint, b: &BigUint) -> BigUint {
+    assert!(a >= b, "subtraction underflow");
+    let mut out = Vec::with_capacity(a.limbs.len());
+    let mut borrow = false;
+    for (i, &x) in a.limbs.iter().enumerate() {
+        let (diff, b1) = x.overflowing_sub(*b.limbs.get(i).unwrap_or(&0));
+        let (diff, b2) = diff.overflowing_sub(borrow as u64);
+        out.push(diff);
+        borrow = b1 || b2;
+    }
+    BigUint::from_limbs(out)
+}
+
+fn mul(a: &BigUint, b: &BigUint) -> BigUint {
+    if a.is_zero() || b.is_zero() {
+        return BigUint::zero();
+    }
+    let mut out = vec![0u64; a.limbs.len() + b.limbs.len()];
+    for (i, &x) in a.limbs.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &y) in b.limbs.iter().enumerate() {
+            let t = x as u128 * y as u128 + out[i + j] as u128 + carry;
+            out[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        out[i + b.limbs.len()] = carry as u64;
+    }
+    BigUint::from_limbs(out)
+}
+
+fn div(a: &BigUint, b: &BigUint) -> BigUint {
+    a.divmod(b).0
+}
+
+fn rem(a: &BigUint, b: &BigUint) -> BigUint {
+    a.divmod(b).1
+}
+
+fn bitand(a: &BigUint, b: &BigUint) -> BigUint {
+    BigUint::from_limbs(
+        a.limbs
+            .iter()
+            .zip(b.limbs.iter())
+            .map(|(x, y)| x & y)
+            .collect(),
+    )
+}
+
+/// Divide limbs by a single limb.
+fn divmod_small(u: &[u64], v: u64) -> (Vec<u64>, u64) {
+    let mut q = vec![0; u.len()];
+    let mut r = 0u128;
+    for i in (0..u.len()).rev() {
+        let num = (r << 64) | u[i] as u128;
+        q[i] = (num / v as u128) as u64;
+        r = num % v as u128;
+    }
+    (q, r as u64)
+}
+
+/// Long division, i.e. Knuth's algorithm D (TAOCP vol. 2, 4.3.1).
+fn divmod_limbs(u: &[u64], v: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    if v.len() == 1 {
+        let (q, r) = divmod_small(u, v[0]);
+        return (q, vec![r]);
+    }
+
+    // Normalize so that the top limb of the divisor has its high bit set
+    let shift = v[v.len() - 1].leading_zeros();
+    let vn = shl_limbs(v, shift, false);
+    let mut un = shl_limbs(u, shift, true);
+    let n = vn.len();
+    let m = u.len() - n;
+    let base = 1u128 << 64;
+
+    let mut q = vec![0u64; m + 1];
+    for j in (0..=m).rev() {
+        let num = ((un[j + n] as u128) << 64) | un[j + n - 1] as u128;
+        let mut qhat = num / vn[n - 1] as u128;
+        let mut rhat = num % vn[n - 1] as u128;
+        while qhat >= base || qhat * vn[n - 2] as u128 > ((rhat << 64) | un[j + n - 2] as u128) {
+            qhat -= 1;
+            rhat += vn[n - 1] as u128;
+            if rhat >= base {
+                break;
+            }
+        }
+
+        // Multiply and subtract
+        let mut borrow = 0i128;
+        let mut carry = 0u128;
+        for i in 0..n {
+            let p = qhat * vn[i] as u128 + carry;
+            carry = p >> 64;
+            let t = un[i + j] as i128 - borrow - (p as u64) as i128;
+            un[i + j] = t as u64;
+            borrow = (t < 0) as i128;
+        }
+        let t = un[j + n] as i128 - borrow - carry as i128;
+        un[j + n] = t as u64;
+
+        // We subtracted one too many times, so add one back
+        if t < 0 {
+            qhat -= 1;
+            let mut carry = 0u128;
+            for i in 0..n {
+                let s = un[i + j] as u128 + vn[i] as u128 + carry;
+                un[i + j] = s as u64;
+                carry = s >> 64;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u64);
+        }
+        q[j] = qhat as u64;
+    }
+
+    let r = shr_limbs(&un[..n], shift);
+    (q, r)
+}
+
+fn shl_limbs(limbs: &[u64], shift: u32, extend: bool) -> Vec<u64> {
+    let mut out = Vec::with_capacity(limbs.len() + 1);
+    let mut carry = 0u64;
+    for &limb in limbs {
+        if shift == 0 {
+            out.push(limb);
+        } else {
+            out.push((limb << shift) | carry);
+            carry = limb >> (64 - shift);
+        }
+    }
+    if extend {
+        out.push(carry);
+    }
+    out
+}
+
+fn shr_limbs(limbs: &[u64], shift: u32) -> Vec<u64> {
+    let mut out = vec![0u64; limbs.len()];
+    for i in 0..limbs.len() {
+        out[i] = if shift == 0 {
+            limbs[i]
+        } else {
+            (limbs[i] >> shift) | limbs.get(i + 1).map_or(0, |next| next << (64 - shift))
+        };
+    }
+    out
+}
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, bits: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let limbs = [
+            vec![0; bits / 64],
+            shl_limbs(&self.limbs, (bits % 64) as u32, true),
+        ]
+        .concat();
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, bits: usize) -> BigUint {
+        &self << bits
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, bits: usize) -> BigUint {
+        if bits / 64 >= self.limbs.len() {
+            return BigUint::zero();
+        }
+        BigUint::from_limbs(shr_limbs(&self.limbs[bits / 64..], (bits % 64) as u32))
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shr(self, bits: usize) -> BigUint {
+        &self >> bits
+    }
+}
+
+// Implement an operator for every combination of owned and borrowed operands,
+// plus a u64 right-hand side for convenience.
+macro_rules! forward_binop {
+    ($t:ty, $imp:ident, $method:ident, $f:expr) => {
+        impl $imp<&$t> for &$t {
+            type Output = $t;
+
+            fn $method(self, rhs: &$t) -> $t {
+                $f(self, rhs)
+            }
+        }
+
+        impl $imp<$t> for $t {
+            type Output = $t;
+
+            fn $method(self, rhs: $t) -> $t {
+                $f(&self, &rhs)
+            }
+        }
+
+        impl $imp<&$t> for $t {
+            type Output = $t;
+
+            fn $method(self, rhs: &$t) -> $t {
+                $f(&self, rhs)
+            }
+        }
+
+        impl $imp<$t> for &$t {
+            type Output = $t;
+
+            fn $method(self, rhs: $t) -> $t {
+                $f(self, &rhs)
+            }
+        }
+
+        impl $imp<u64> for &$t {
+            type Output = $t;
+
+            fn $method(self, rhs: u64) -> $t {
+                $f(self, &<$t>::from(rhs))
+            }
+        }
+
+        impl $imp<u64> for $t {
+            type Output = $t;
+
+            fn $method(self, rhs: u64) -> $t {
+                $f(&self, &<$t>::from(rhs))
+            }
+        }
+    };
+}
+
+forward_binop!(BigUint, Add, add, add);
+forward_binop!(BigUint, Sub, sub, sub);
+forward_binop!(BigUint, Mul, mul, mul);
+forward_binop!(BigUint, Div, div, div);
+forward_binop!(BigUint, Rem, rem, rem);
+forward_binop!(BigUint, BitAnd, bitand, bitand);
+
+/// Montgomery form arithmetic for odd moduli, which lets modular
+/// exponentiation skip the long division on every step.
+struct Montgomery {
+    n: Vec<u64>,
+    n_inv: u64,
+    r2: BigUint,
+}
+
+impl Montgomery {
+    fn new(modulus: &BigUint) -> Self {
+        let n = modulus.limbs.clone();
+
+        // -n^-1 mod 2^64 by Newton iteration (each step doubles the bits)
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(n[0].wrapping_mul(inv)));
+        }
+
+        let r2 = &(BigUint::one() << (128 * n.len())) % modulus;
+        Self {
+            n,
+            n_inv: inv.wrapping_neg(),
+            r2,
+        }
+    }
+
+    fn pad(&self, x: &BigUint) -> Vec<u64> {
+        let mut limbs = x.limbs.clone();
+        limbs.resize(self.n.len(), 0);
+        limbs
+    }
+
+    /// Compute a * b / R mod n (coarsely integrated operand scanning).
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let k = self.n.len();
+        let mut t = vec![0u64; k + 2];
+        for &bi in b.iter() {
+            let mut carry = 0u128;
+            for j in 0..k {
+                let s = t[j] as u128 + a[j] as u128 * bi as u128 + carry;
+                t[j] = s as u64;
+                carry = s >> 64;
+            }
+            let s = t[k] as u128 + carry;
+            t[k] = s as u64;
+            t[k + 1] = (s >> 64) as u64;
+
+            let m = t[0].wrapping_mul(self.n_inv);
+            let s = t[0] as u128 + m as u128 * self.n[0] as u128;
+            let mut carry = s >> 64;
+            for j in 1..k {
+                let s = t[j] as u128 + m as u128 * self.n[j] as u128 + carry;
+                t[j - 1] = s as u64;
+                carry = s >> 64;
+            }
+            let s = t[k] as u128 + carry;
+            t[k - 1] = s as u64;
+            t[k] = t[k + 1] + (s >> 64) as u64;
+            t[k + 1] = 0;
+        }
+
+        // Final conditional subtraction
+        let ge = t[k] != 0 || t[..k].iter().rev().cmp(self.n.iter().rev()) != Ordering::Less;
+        if ge {
+            let mut borrow = false;
+            for (tj, nj) in t.iter_mut().zip(self.n.iter()) {
+                let (d, b1) = tj.overflowing_sub(*nj);
+                let (d, b2) = d.overflowing_sub(borrow as u64);
+                *tj = d;
+                borrow = b1 || b2;
+            }
+        }
+        t.truncate(k);
+        t
+    }
+
+    fn pow(&self, base: &BigUint, exp: &BigUint) -> BigUint {
+        let modulus = BigUint::from_limbs(self.n.clone());
+        let base = self.mul(&self.pad(&(base % &modulus)), &self.pad(&self.r2));
+        let one = self.mul(&self.pad(&BigUint::one()), &self.pad(&self.r2));
+
+        // Fixed 4-bit window
+        let mut table = vec![one.clone(), base.clone()];
+        for i in 2..16 {
+            table.push(self.mul(&table[i - 1], &base));
+        }
+
+        let mut out = one;
+        let windows = (exp.bits() + 3) / 4;
+        for w in (0..windows).rev() {
+            for _ in 0..4 {
+                out = self.mul(&out, &out);
+            }
+            let digit = (0..4).fold(0, |d, i| d | ((exp.bit(w * 4 + i) as usize) << i));
+            if digit != 0 {
+                out = self.mul(&out, &table[digit]);
+            }
+        }
+
+        let mut unit = vec![0; self.n.len()];
+        unit[0] = 1;
+        BigUint::from_limbs(self.mul(&out, &unit))
+    }
+}
+
+/// Arbitrary-precision signed integer (sign and magnitude).
+///
+/// # Examples
+/// ```
+/// use cpr::bigint::{BigInt, BigUint};
+/// let a = BigInt::from(-7i64);
+/// let b = BigInt::from(3i64);
+/// assert_eq!(&a + &b, BigInt::from(-4i64));
+/// assert_eq!(&a * &b, BigInt::from(-21i64));
+/// assert_eq!(&b - &a, BigInt::from(10i64));
+/// assert_eq!(a.modulo(&BigUint::from(5u64)), BigUint::from(3u64));
+/// assert_eq!(a.to_string(), "-7");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn new(negative: bool, magnitude: BigUint) -> Self {
+        Self {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    /// Reduce into `[0, modulus)`.
+    pub fn modulo(&self, modulus: &BigUint) -> BigUint {
+        let r = &self.magnitude % modulus;
+        if self.negative && !r.is_zero() {
+            modulus - &r
+        } else {
+            r
+        }
+    }
+
+    /// Non-negative value, if there is one.
+    pub fn to_biguint(&self) -> Option<BigUint> {
+        (!self.negative).then(|| self.magnitude.clone())
+    }
+
+    /// Truncating division, like Rust's primitive integers.
+    pub fn divmod(&self, other: &Self) -> (Self, Self) {
+        let (q, r) = self.magnitude.divmod(&other.magnitude);
+        (
+            Self::new(self.negative != other.negative, q),
+            Self::new(self.negative, r),
+        )
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        Self::new(false, magnitude)
+    }
+}
+
+impl From<&BigUint> for BigInt {
+    fn from(magnitude: &BigUint) -> Self {
+        Self::new(false, magnitude.clone())
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(n: i64) -> Self {
+        Self::new(n < 0, BigUint::from(n.unsigned_abs()))
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(n: u64) -> Self {
+        Self::new(false, BigUint::from(n))
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('-') {
+            Some(rest) => Ok(Self::new(true, rest.parse()?)),
+            None => Ok(Self::new(false, s.parse()?)),
+        }
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude.clone())
+    }
+}
+
+fn signed_add(a: &BigInt, b: &BigInt) -> BigInt {
+    if a.negative == b.negative {
+        return BigInt::new(a.negative, &a.magnitude + &b.magnitude);
+    }
+    if a.magnitude >= b.magnitude {
+        BigInt::new(a.negative, &a.magnitude - &b.magnitude)
+    } else {
+        BigInt::new(b.negative, &b.magnitude - &a.magnitude)
+    }
+}
+
+fn signed_sub(a: &BigInt, b: &BigInt) -> BigInt {
+    signed_add(a, &-b)
+}
+
+fn signed_mul(a: &BigInt, b: &BigInt) -> BigInt {
+    BigInt::new(a.negative != b.negative, &a.magnitude * &b.magnitude)
+}
+
+fn signed_div(a: &BigInt, b: &BigInt) -> BigInt {
+    a.divmod(b).0
+}
+
+fn signed_rem(a: &BigInt, b: &BigInt) -> BigInt {
+    a.divmod(b).1
+}
+
+forward_binop!(BigInt, Add, add, signed_add);
+forward_binop!(BigInt, Sub, sub, signed_sub);
+forward_binop!(BigInt, Mul, mul, signed_mul);
+forward_binop!(BigInt, Div, div, signed_div);
+forward_binop!(BigInt, Rem, rem, signed_rem);
+
+/// Extended Euclidean algorithm: returns `(g, x, y)` such that
+/// `a * x + b * y = g = gcd(a, b)`.
+///
+/// # Examples
+/// ```
+/// use cpr::bigint::{extended_gcd, BigInt, BigUint};
+/// let (a, b) = (BigUint::from(240u64), BigUint::from(46u64));
+/// let (g, x, y) = extended_gcd(&a, &b);
+/// assert_eq!(g, BigUint::from(2u64));
+/// assert_eq!(&(&BigInt::from(a) * &x) + &(&BigInt::from(b) * &y), BigInt::from(g));
+/// ```
+pub fn extended_gcd(a: &BigUint, b: &BigUint) -> (BigUint, BigInt, BigInt) {
+    let (mut old_r, mut r) = (a.clone(), b.clone());
+    let (mut old_s, mut s) = (BigInt::from(1i64), BigInt::zero());
+    let (mut old_t, mut t) = (BigInt::zero(), BigInt::from(1i64));
+    while !r.is_zero() {
+        let (q, rem) = old_r.divmod(&r);
+        let q = BigInt::from(q);
+        old_r = std::mem::replace(&mut r, rem);
+        let next_s = &old_s - &(&q * &s);
+        old_s = std::mem::replace(&mut s, next_s);
+        let next_t = &old_t - &(&q * &t);
+        old_t = std::mem::replace(&mut t, next_t);
+    }
+    (old_r, old_s, old_t)
+}
+
+/// Chinese remainder theorem: find the unique `x` mod `prod(moduli)` with
+/// `x = residues[i] mod moduli[i]`. Moduli must be pairwise coprime.
+///
+/// # Examples
+/// ```
+/// use cpr::bigint::{crt, BigUint};
+/// let residues = [2u64, 3, 2].map(BigUint::from);
+/// let moduli = [3u64, 5, 7].map(BigUint::from);
+/// assert_eq!(crt(&residues, &moduli), Some(BigUint::from(23u64)));
+/// ```
+pub fn crt(residues: &[BigUint], moduli: &[BigUint]) -> Option<BigUint> {
+    let product = moduli.iter().fold(BigUint::one(), |acc, m| &acc * m);
+    let mut x = BigUint::zero();
+    for (r, m) in residues.iter().zip(moduli.iter()) {
+        let ms = &product / m;
+        x = &x + &(&(r * &ms) * &ms.modinv(m)?);
+    }
+    Some(&x % &product)
+}
@@ -25,6 +25,7 @@ enum Mode {
     CBC,
 }
 
+#[allow(clippy::useless_conversion)]
 fn encryption_oracle(data: &[u8], rng: &mut ThreadRng) -> (Vec<u8>, Mode) {
     let key = rand_block(rng);
     let mut plaintext = rand_pad(rng)
@@ -59,6 +59,7 @@ struct Cookie {
 impl FromStr for Cookie {
     type Err = Box<dyn Error>;
 
+    #[allow(clippy::manual_try_fold)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         s.trim()
             .split('&')
@@ -91,6 +92,7 @@ impl FromStr for Cookie {
     }
 }
 
+#[allow(clippy::to_string_trait_impl)]
 impl ToString for Cookie {
     fn to_string(&self) -> String {
         format!("email={}&uid={}&role={}", self.email, self.uid, self.role)
@@ -112,6 +114,7 @@ impl Cookie {
         self.role == "admin"
     }
 
+    #[allow(clippy::collapsible_str_replace)]
     fn profile_for(email: &str) -> Self {
         let email = email.replace('&', "").replace('=', "");
         Cookie {
@@ -122,6 +125,7 @@ impl Cookie {
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn make_oracle() -> (Vec<u8>, impl Fn(Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>>) {
     let key = utils::rand_bytes(16);
     (key.clone(), move |input| {
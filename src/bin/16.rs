@@ -51,6 +51,7 @@ fn encrypt(pt: Vec<u8>, key: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
     Ok([iv.clone(), utils::cbc(&full, key, &iv, utils::Op::Encrypt)].concat())
 }
 
+#[allow(clippy::type_complexity)]
 fn make_oracles() -> (
     impl Fn(Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>>,
     impl Fn(Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>>,
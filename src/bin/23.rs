@@ -47,6 +47,7 @@ fn clone(prng: &mut MT19937) -> MT19937 {
 
 // Oh good grief, I absolutely detest bit magic. (╯°□°）╯︵ ┻━┻
 // The following code is mostly cargo-culted from other people's solutions.
+#[allow(clippy::legacy_numeric_constants)]
 fn undo_right_shift_xor(x: u32, shift: u32) -> u32 {
     let w = 32;
     let mut y = (u32::max_value() << (w - shift)) & x;
@@ -60,6 +61,7 @@ fn undo_right_shift_xor(x: u32, shift: u32) -> u32 {
     y
 }
 
+#[allow(clippy::legacy_numeric_constants)]
 fn undo_left_shift_xor(x: u32, shift: u32, mask: u32) -> u32 {
     let w = 32;
     let mut y = (u32::max_value() >> (w - shift)) & x;
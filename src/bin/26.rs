@@ -54,6 +54,7 @@ fn encrypt(pt: Vec<u8>, key: &[u8], nonce: u64) -> Vec<u8> {
     utils::ctr(&full, key, nonce)
 }
 
+#[allow(clippy::type_complexity)]
 fn make_oracles() -> (impl Fn(Vec<u8>) -> Vec<u8>, impl Fn(Vec<u8>) -> Vec<u8>) {
     let ke = utils::rand_bytes(16);
     let kd = ke.clone();
@@ -67,6 +67,7 @@ fn validate(pt: &[u8]) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[allow(clippy::type_complexity)]
 fn make_oracles() -> (
     impl Fn(Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>>,
     impl Fn(Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>>,
@@ -0,0 +1,88 @@
+// Break a SHA-1 keyed MAC using length extension
+use cpr::{
+    hash::sha1::{self, Sha1},
+    utils,
+};
+use rand::Rng;
+use std::error::Error;
+
+const MESSAGE: &[u8] =
+    b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+const SUFFIX: &[u8] = b";admin=true";
+
+pub fn solve(_input: &str) -> Option<String> {
+    let (mac, verify) = make_oracle(MESSAGE);
+    let (msg, _forged_mac) = attack(MESSAGE, &mac, &verify)?;
+    Some(utils::utf8_decode(&msg))
+}
+
+fn attack(
+    known_msg: &[u8],
+    mac: &[u8],
+    oracle: &impl Fn(&[u8], &[u8]) -> bool,
+) -> Option<(Vec<u8>, Vec<u8>)> {
+    // We don't know how long the key is, so brute force it: every guess
+    // produces a different glue padding, and only the right one will verify.
+    (0..=128)
+        .map(|key_len_guess| length_extend(mac, known_msg, key_len_guess, SUFFIX))
+        .find(|(msg, mac)| oracle(msg, mac))
+}
+
+/// Forge a MAC for `known_msg || glue || suffix` given the MAC of `known_msg`
+/// and a guess at the length of the secret key.
+fn length_extend(
+    mac: &[u8],
+    known_msg: &[u8],
+    key_len_guess: usize,
+    suffix: &[u8],
+) -> (Vec<u8>, Vec<u8>) {
+    let len = (key_len_guess + known_msg.len()) as u64;
+    let glue = sha1::md_padding(len);
+
+    // Pick up the hash where the server left off, i.e. right after it hashed
+    // key || message || glue.
+    let mut sha = Sha1::from_state(sha1::from_digest(mac), len + glue.len() as u64);
+    sha.update(suffix);
+
+    let msg = [known_msg, &glue, suffix].concat();
+    (msg, sha.finalize().to_vec())
+}
+
+fn sign(msg: &[u8], key: &[u8]) -> Vec<u8> {
+    sha1::digest(&[key, msg].concat()).to_vec()
+}
+
+fn make_oracle(msg: &[u8]) -> (Vec<u8>, impl Fn(&[u8], &[u8]) -> bool) {
+    let mut rng = rand::thread_rng();
+    let key = utils::rand_bytes(rng.gen_range(1..=64));
+    let mac = sign(msg, &key);
+    (mac, move |msg: &[u8], mac: &[u8]| sign(msg, &key) == mac)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(29, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let has = ";admin=true";
+        let got = solve("");
+        assert!(got.unwrap().contains(has));
+        Ok(())
+    }
+
+    #[test]
+    fn test_length_extend() {
+        let key = b"YELLOW SUBMARINE";
+        let mac = sign(MESSAGE, key);
+        let (msg, forged) = length_extend(&mac, MESSAGE, key.len(), SUFFIX);
+        assert!(msg.starts_with(MESSAGE));
+        assert!(msg.ends_with(SUFFIX));
+        assert_eq!(sign(&msg, key), forged);
+    }
+}
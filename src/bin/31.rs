@@ -0,0 +1,53 @@
+// Implement and break HMAC-SHA1 with an artificial timing leak
+use cpr::{
+    timing::{self, AttackConfig, Server},
+    utils,
+};
+use std::{error::Error, time::Duration};
+
+const FILE: &str = "foo";
+
+pub fn solve(_input: &str) -> Option<String> {
+    let key = utils::rand_bytes(16);
+    let server = Server::start(&key, Duration::from_millis(50), 0).ok()?;
+
+    // With a 50ms delay, a single sample per byte is plenty
+    let config = AttackConfig {
+        samples: 1,
+        ..Default::default()
+    };
+    let mac = timing::recover_mac(server.addr(), FILE, 20, &config)?;
+    let (ok, _) = timing::request(server.addr(), FILE, &mac).ok()?;
+    ok.then(|| hex::encode(mac))
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(31, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let got = solve("");
+        assert!(got.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncated() -> Result<(), Box<dyn Error>> {
+        let key = utils::rand_bytes(16);
+        let server = Server::start_truncated(&key, Duration::from_millis(2), 0, 3)?;
+        let config = AttackConfig {
+            samples: 3,
+            ..Default::default()
+        };
+        let mac = timing::recover_mac(server.addr(), FILE, 3, &config).ok_or("no mac")?;
+        assert!(timing::request(server.addr(), FILE, &mac)?.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+// Break HMAC-SHA1 with a slightly less artificial timing leak
+use cpr::{
+    timing::{self, AttackConfig, Server, Statistic},
+    utils,
+};
+use std::{error::Error, time::Duration};
+
+const FILE: &str = "foo";
+
+pub fn solve(_input: &str) -> Option<String> {
+    let key = utils::rand_bytes(16);
+    let server = Server::start(&key, Duration::from_millis(5), 0).ok()?;
+    let mac = attack(&server, 20)?;
+    let (ok, _) = timing::request(server.addr(), FILE, &mac).ok()?;
+    ok.then(|| hex::encode(mac))
+}
+
+fn attack(server: &Server, mac_len: usize) -> Option<Vec<u8>> {
+    // The leak is now small enough that scheduler jitter and the odd slow
+    // round trip matter, so take more samples, throw out the outliers and let
+    // the attack resample the close calls.
+    let config = AttackConfig {
+        samples: 7,
+        retries: 16,
+        statistic: Statistic::TrimmedMean(0.2),
+        ..Default::default()
+    };
+    timing::recover_mac(server.addr(), FILE, mac_len, &config)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(32, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let got = solve("");
+        assert!(got.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sub_millisecond() -> Result<(), Box<dyn Error>> {
+        let key = utils::rand_bytes(16);
+        let server = Server::start_truncated(&key, Duration::from_micros(500), 0, 3)?;
+        let mac = attack(&server, 3).ok_or("no mac")?;
+        assert!(timing::request(server.addr(), FILE, &mac)?.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+// Implement Diffie-Hellman
+use cpr::dh::{self, Group};
+use std::error::Error;
+
+pub fn solve(_input: &str) -> Option<String> {
+    // Warm up with some small numbers...
+    let toy = Group::new(37u64.into(), 5u64.into());
+    let (a, b) = (toy.keypair(), toy.keypair());
+    if a.shared_secret(&toy, &b.public) != b.shared_secret(&toy, &a.public) {
+        return None;
+    }
+
+    // ...then do it for real
+    let group = Group::nist();
+    let (a, b) = (group.keypair(), group.keypair());
+    let s = a.shared_secret(&group, &b.public);
+    if s != b.shared_secret(&group, &a.public) {
+        return None;
+    }
+    Some(hex::encode(dh::derive_key(&s)))
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(33, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cpr::bigint::BigUint;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let got = solve("").ok_or("no solution")?;
+        assert_eq!(got.len(), 32);
+        Ok(())
+    }
+
+    #[test]
+    fn test_toy_group() {
+        let toy = Group::new(37u64.into(), 5u64.into());
+        let a = dh::Keypair {
+            private: 4u64.into(),
+            public: BigUint::from(5u64).modpow(&4u64.into(), &toy.p),
+        };
+        assert_eq!(a.public, BigUint::from(33u64));
+        assert_eq!(a.shared_secret(&toy, &8u64.into()), BigUint::from(26u64));
+    }
+}
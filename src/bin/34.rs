@@ -0,0 +1,75 @@
+// Implement a MITM key-fixing attack on Diffie-Hellman with parameter injection
+use cpr::{
+    bigint::BigUint,
+    dh::{self, Direction, Group, Message},
+    utils,
+};
+use std::error::Error;
+
+const MESSAGE: &[u8] = b"Hello Bob, it's me, Alice. Let's meet at noon.";
+
+pub fn solve(_input: &str) -> Option<String> {
+    let intercepted = attack(MESSAGE).ok()?;
+    Some(
+        intercepted
+            .iter()
+            .map(|pt| utils::utf8_decode(pt))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+fn attack(msg: &[u8]) -> dh::Result<Vec<Vec<u8>>> {
+    let mut group = None;
+    let mut data = vec![];
+
+    let (alice, bob) = dh::exchange(Group::nist(), msg, |_: Direction, msg| match msg {
+        Message::Negotiate(g) => {
+            group = Some(g.clone());
+            Message::Negotiate(g)
+        }
+        // Swap both public keys out for p, so that everyone's shared secret
+        // ends up as p^x mod p = 0.
+        Message::PublicKey(_) => Message::PublicKey(group.as_ref().unwrap().p.clone()),
+        Message::Data { .. } => {
+            data.push(msg.clone());
+            msg
+        }
+    })?;
+
+    // Alice and Bob should be none the wiser
+    if alice != msg || bob != msg {
+        return Err("MITM was detected".into());
+    }
+
+    let key = dh::derive_key(&BigUint::zero());
+    Ok(data
+        .iter()
+        .filter_map(|msg| dh::decrypt(msg, &key))
+        .collect())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(34, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let want = [MESSAGE, MESSAGE].map(utils::utf8_decode).join("\n");
+        let got = solve("");
+        assert_eq!(Some(want), got);
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_mitm() {
+        let (alice, bob) = dh::exchange(Group::nist(), MESSAGE, |_, msg| msg).unwrap();
+        assert_eq!(alice, MESSAGE);
+        assert_eq!(bob, MESSAGE);
+    }
+}
@@ -0,0 +1,107 @@
+// Implement DH with negotiated groups, and break with malicious "g" parameters
+use cpr::{
+    bigint::BigUint,
+    dh::{self, Direction, Group, Message},
+    utils,
+};
+use std::error::Error;
+
+const MESSAGE: &[u8] = b"Hello Bob, it's me, Alice. Let's meet at noon.";
+
+#[derive(Clone, Copy, Debug)]
+enum Evil {
+    One,
+    P,
+    PMinusOne,
+}
+
+pub fn solve(_input: &str) -> Option<String> {
+    let mut out = vec![];
+    for evil in [Evil::One, Evil::P, Evil::PMinusOne] {
+        let intercepted = attack(MESSAGE, evil).ok()?;
+        if intercepted.is_empty() {
+            return None;
+        }
+        for pt in intercepted {
+            out.push(format!("g = {:?}: {}", evil, utils::utf8_decode(&pt)));
+        }
+    }
+    Some(out.join("\n"))
+}
+
+fn attack(msg: &[u8], evil: Evil) -> dh::Result<Vec<Vec<u8>>> {
+    let mut group = None;
+    let mut publics = vec![];
+    let mut data = vec![];
+
+    dh::exchange(Group::nist(), msg, |direction, msg| match msg {
+        // Slip Bob a bad generator. He echoes it back as the agreed group, so
+        // Alice ends up using it too.
+        Message::Negotiate(g) if direction == Direction::AliceToBob => {
+            let g = Group::new(g.p.clone(), bad_generator(&g.p, evil));
+            group = Some(g.clone());
+            Message::Negotiate(g)
+        }
+        Message::PublicKey(ref public) => {
+            publics.push(public.clone());
+            msg
+        }
+        Message::Data { .. } => {
+            data.push(msg.clone());
+            msg
+        }
+        _ => msg,
+    })?;
+
+    let p = group.ok_or("no negotiation")?.p;
+    let s = match evil {
+        // 1^x = 1
+        Evil::One => BigUint::one(),
+        // p^x = 0 mod p
+        Evil::P => BigUint::zero(),
+        // (-1)^ab is -1 only when a and b are both odd, which is exactly when
+        // both public keys are -1 too
+        Evil::PMinusOne if publics.iter().all(|public| public == &(&p - 1)) => &p - 1,
+        Evil::PMinusOne => BigUint::one(),
+    };
+
+    let key = dh::derive_key(&s);
+    Ok(data
+        .iter()
+        .filter_map(|msg| dh::decrypt(msg, &key))
+        .collect())
+}
+
+fn bad_generator(p: &BigUint, evil: Evil) -> BigUint {
+    match evil {
+        Evil::One => BigUint::one(),
+        Evil::P => p.clone(),
+        Evil::PMinusOne => p - 1,
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(35, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let has = "g = PMinusOne: Hello Bob";
+        let got = solve("");
+        assert!(got.unwrap().contains(has));
+        Ok(())
+    }
+
+    #[test]
+    fn test_attacks() {
+        for evil in [Evil::One, Evil::P, Evil::PMinusOne] {
+            let got = attack(MESSAGE, evil).unwrap();
+            assert_eq!(got, vec![MESSAGE.to_vec(), MESSAGE.to_vec()]);
+        }
+    }
+}
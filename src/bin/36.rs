@@ -0,0 +1,41 @@
+// Implement Secure Remote Password (SRP)
+use cpr::srp::{self, Client, Listener, Params, Server, Variant};
+use std::error::Error;
+
+const EMAIL: &str = "alice@example.com";
+const PASSWORD: &[u8] = b"correct horse battery staple";
+
+pub fn solve(_input: &str) -> Option<String> {
+    let mut server = Server::new(Params::nist(), Variant::Srp);
+    server.register(EMAIL, PASSWORD);
+    let listener = Listener::start(server, 0).ok()?;
+
+    let good = Client::new(Params::nist(), Variant::Srp, EMAIL, PASSWORD);
+    let bad = Client::new(Params::nist(), Variant::Srp, EMAIL, b"Tr0ub4dor&3");
+    let ok = srp::login(listener.addr(), &good).ok()?;
+    let rejected = !srp::login(listener.addr(), &bad).ok()?;
+
+    Some(format!(
+        "Right password: {}\nWrong password: {}",
+        if ok { "OK" } else { "FAIL" },
+        if rejected { "rejected" } else { "accepted" },
+    ))
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(36, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let want = Some("Right password: OK\nWrong password: rejected".into());
+        let got = solve("");
+        assert_eq!(want, got);
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+// Break SRP with a zero key
+use cpr::{
+    bigint::BigUint,
+    srp::{self, Connection, Listener, Message, Params, Server, Variant},
+    utils,
+};
+use std::{error::Error, net::SocketAddr};
+
+const EMAIL: &str = "alice@example.com";
+
+pub fn solve(_input: &str) -> Option<String> {
+    // The server has a password on file that we know nothing about
+    let mut server = Server::new(Params::nist(), Variant::Srp);
+    server.register(EMAIL, &utils::rand_bytes(32));
+    let listener = Listener::start(server, 0).ok()?;
+
+    let n = Params::nist().n;
+    let mut out = vec![];
+    for (name, a) in [("0", BigUint::zero()), ("N", n.clone()), ("2N", &n * 2)] {
+        let ok = attack(listener.addr(), a).ok()?;
+        out.push(format!("A = {}: {}", name, if ok { "OK" } else { "FAIL" }));
+    }
+    Some(out.join("\n"))
+}
+
+fn attack(addr: SocketAddr, a: BigUint) -> Result<bool, Box<dyn Error>> {
+    let mut conn = Connection::connect(addr)?;
+    conn.send(&Message::Hello {
+        email: EMAIL.into(),
+        a,
+    })?;
+    let Message::Challenge { salt, .. } = conn.recv()? else {
+        return Err("expected challenge".into());
+    };
+
+    // The server computes S = (A * v^u)^b mod N, and A is a multiple of N, so
+    // S = 0 no matter what the password is.
+    let key = srp::session_key(&BigUint::zero());
+    conn.send(&Message::Proof(srp::proof(&key, &salt)))?;
+
+    match conn.recv()? {
+        Message::Verdict(ok) => Ok(ok),
+        _ => Err("expected verdict".into()),
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(37, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let want = Some("A = 0: OK\nA = N: OK\nA = 2N: OK".into());
+        let got = solve("");
+        assert_eq!(want, got);
+        Ok(())
+    }
+}
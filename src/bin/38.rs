@@ -0,0 +1,96 @@
+// Offline dictionary attack on simplified SRP
+use cpr::{
+    bigint::BigUint,
+    srp::{self, Client, Connection, Message, Params, Variant},
+};
+use rand::seq::SliceRandom;
+use std::{error::Error, net::TcpListener, thread};
+
+const EMAIL: &str = "alice@example.com";
+
+pub fn solve(input: &str) -> Option<String> {
+    let words = input.lines().map(|l| l.trim()).collect::<Vec<_>>();
+    let password = words.choose(&mut rand::thread_rng())?.to_string();
+
+    // Pose as the server and wait for an unsuspecting client to log in
+    let listener = TcpListener::bind("127.0.0.1:0").ok()?;
+    let addr = listener.local_addr().ok()?;
+    let client = thread::spawn(move || {
+        let client = Client::new(
+            Params::nist(),
+            Variant::Simplified,
+            EMAIL,
+            password.as_bytes(),
+        );
+        srp::login(addr, &client).ok()
+    });
+
+    let (a, proof) = mitm(&listener).ok()?;
+    client.join().ok()?;
+
+    crack(&a, &proof, &words).map(String::from)
+}
+
+/// Play the server with b = 1, u = 1 and an empty salt, and capture the
+/// client's A and proof.
+fn mitm(listener: &TcpListener) -> Result<(BigUint, Vec<u8>), Box<dyn Error>> {
+    let (stream, _) = listener.accept()?;
+    let mut conn = Connection::new(stream)?;
+    let Message::Hello { a, .. } = conn.recv()? else {
+        return Err("expected hello".into());
+    };
+    conn.send(&Message::Challenge {
+        salt: vec![],
+        b: Params::nist().g,
+        u: Some(BigUint::one()),
+    })?;
+    let Message::Proof(proof) = conn.recv()? else {
+        return Err("expected proof".into());
+    };
+    conn.send(&Message::Verdict(false))?;
+    Ok((a, proof))
+}
+
+/// With B = g and u = 1, the client computed S = g^(a + x) = A * g^x, so
+/// each guess costs one modpow and one HMAC.
+fn crack<'a>(a: &BigUint, proof: &[u8], words: &[&'a str]) -> Option<&'a str> {
+    let Params { n, g, .. } = Params::nist();
+    words.iter().copied().find(|word| {
+        let x = srp::private_key(b"", word.as_bytes());
+        let s = &(a * &g.modpow(&x, &n)) % &n;
+        srp::proof(&srp::session_key(&s), b"") == proof
+    })
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let input = &cpr::read_data(38, false)?;
+    cpr::solve!(38, solve, input).ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let input = &cpr::read_data(38, false)?;
+        let got = solve(input).ok_or("no solution")?;
+        assert!(input.lines().any(|l| l == got));
+        Ok(())
+    }
+
+    #[test]
+    fn test_crack() -> Result<(), Box<dyn Error>> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let client = thread::spawn(move || {
+            let client = Client::new(Params::nist(), Variant::Simplified, EMAIL, b"letmein");
+            srp::login(addr, &client).ok()
+        });
+        let (a, proof) = mitm(&listener)?;
+        client.join().unwrap();
+        assert_eq!(crack(&a, &proof, &["123456", "letmein"]), Some("letmein"));
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+// Implement RSA
+use cpr::{bigint::BigUint, rsa};
+use std::error::Error;
+
+pub fn solve(input: &str) -> Option<String> {
+    let (public, private) = rsa::keygen(1024, 3);
+    let m = BigUint::from_bytes_be(input.trim().as_bytes());
+    let c = public.encrypt(&m);
+    let pt = private.decrypt(&c).to_bytes_be();
+    String::from_utf8(pt).ok()
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(39, solve, "YELLOW SUBMARINE").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let want = Some("YELLOW SUBMARINE".into());
+        let got = solve("YELLOW SUBMARINE");
+        assert_eq!(want, got);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invmod() {
+        let got = BigUint::from(17u64).modinv(&3120u64.into());
+        assert_eq!(got, Some(2753u64.into()));
+    }
+
+    #[test]
+    fn test_numbers() {
+        for bits in [128, 256, 512] {
+            let (public, private) = rsa::keygen(bits, 3);
+            let m = BigUint::from(42u64);
+            assert_eq!(private.decrypt(&public.encrypt(&m)), m);
+        }
+    }
+}
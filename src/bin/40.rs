@@ -0,0 +1,64 @@
+// Implement an E=3 RSA Broadcast attack
+use cpr::{
+    bigint::{self, BigUint},
+    rsa::{self, PublicKey},
+};
+use std::error::Error;
+
+pub fn solve(input: &str) -> Option<String> {
+    let m = BigUint::from_bytes_be(input.trim().as_bytes());
+    let captured = (0..3)
+        .map(|_| {
+            let (public, _) = rsa::keygen(1024, 3);
+            (public.encrypt(&m), public)
+        })
+        .collect::<Vec<_>>();
+    let pt = attack(&captured)?;
+    String::from_utf8(pt.to_bytes_be()).ok()
+}
+
+/// Recover a message encrypted under three different e = 3 public keys.
+fn attack(captured: &[(BigUint, PublicKey)]) -> Option<BigUint> {
+    // CRT the ciphertexts together to get m^3 mod n1 * n2 * n3. Since m is
+    // smaller than each modulus, m^3 is smaller than their product, so that's
+    // just m^3 and we can take a plain integer cube root.
+    let (cts, moduli): (Vec<_>, Vec<_>) = captured
+        .iter()
+        .map(|(c, public)| (c.clone(), public.n.clone()))
+        .unzip();
+    let cubed = bigint::crt(&cts, &moduli)?;
+    let m = cubed.nth_root(3);
+    (m.pow(3) == cubed).then_some(m)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(40, solve, "YELLOW SUBMARINE").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let want = Some("YELLOW SUBMARINE".into());
+        let got = solve("YELLOW SUBMARINE");
+        assert_eq!(want, got);
+        Ok(())
+    }
+
+    #[test]
+    fn test_random_messages() {
+        for bits in [128, 256, 512] {
+            let keys = (0..3).map(|_| rsa::keygen(bits, 3).0).collect::<Vec<_>>();
+            let smallest = keys.iter().map(|k| &k.n).min().unwrap();
+            let m = BigUint::random_below(smallest);
+            let captured = keys
+                .into_iter()
+                .map(|public| (public.encrypt(&m), public))
+                .collect::<Vec<_>>();
+            assert_eq!(attack(&captured), Some(m));
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// Implement unpadded message recovery oracle
+use cpr::{
+    bigint::BigUint,
+    hash::sha256,
+    rsa::{self, PublicKey},
+};
+use std::{cell::RefCell, collections::HashSet, error::Error};
+
+pub fn solve(input: &str) -> Option<String> {
+    let (public, oracle) = make_oracle();
+
+    // Some victim submits their message to the server, and we sniff it
+    let m = BigUint::from_bytes_be(input.trim().as_bytes());
+    let c = public.encrypt(&m);
+    oracle(&c).ok()?;
+
+    // The server won't decrypt it twice...
+    if oracle(&c).is_ok() {
+        return None;
+    }
+
+    // ...but it will happily decrypt something that looks different
+    let pt = attack(&c, &public, &oracle)?;
+    String::from_utf8(pt.to_bytes_be()).ok()
+}
+
+fn attack(
+    c: &BigUint,
+    public: &PublicKey,
+    oracle: &impl Fn(&BigUint) -> Result<BigUint, Box<dyn Error>>,
+) -> Option<BigUint> {
+    let n = &public.n;
+
+    // Blind the ciphertext: C' = S^e * C, which decrypts to P' = S * P
+    let s = loop {
+        let s = BigUint::random_range(&2u64.into(), n);
+        if s.gcd(n).is_one() {
+            break s;
+        }
+    };
+    let blinded = &(&public.encrypt(&s) * c) % n;
+    let p = oracle(&blinded).ok()?;
+
+    // Unblind: P = P' / S
+    Some(&(&p * &s.modinv(n)?) % n)
+}
+
+/// A server that decrypts any ciphertext, but only once.
+#[allow(clippy::type_complexity)]
+fn make_oracle() -> (
+    PublicKey,
+    impl Fn(&BigUint) -> Result<BigUint, Box<dyn Error>>,
+) {
+    let (public, private) = rsa::keygen(1024, 65537);
+    let seen = RefCell::new(HashSet::new());
+    (public, move |c: &BigUint| {
+        if !seen.borrow_mut().insert(sha256::digest(&c.to_bytes_be())) {
+            return Err("already decrypted".into());
+        }
+        Ok(private.decrypt(c))
+    })
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let input = r#"{time: 1356304276, social: '555-55-5555'}"#;
+    cpr::solve!(41, solve, input).ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"{time: 1356304276, social: '555-55-5555'}"#;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let want = Some(EXAMPLE.into());
+        let got = solve(EXAMPLE);
+        assert_eq!(want, got);
+        Ok(())
+    }
+
+    #[test]
+    fn test_oracle() {
+        let (public, oracle) = make_oracle();
+        let m = BigUint::from(42u64);
+        let c = public.encrypt(&m);
+        assert_eq!(oracle(&c).unwrap(), m);
+        assert!(oracle(&c).is_err());
+    }
+}
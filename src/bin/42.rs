@@ -0,0 +1,78 @@
+// Bleichenbacher's e=3 RSA Attack
+use cpr::{
+    bigint::BigUint,
+    hash::sha1::Sha1,
+    rsa::{self, DigestInfo, PublicKey},
+};
+use std::error::Error;
+
+pub fn solve(input: &str) -> Option<String> {
+    let msg = input.trim().as_bytes();
+    let (public, _) = rsa::keygen(1024, 3);
+    let sig = forge::<Sha1>(msg, &public)?;
+
+    // Only the broken verifier falls for it
+    if !public.verify_sloppy::<Sha1>(msg, &sig) || public.verify::<Sha1>(msg, &sig) {
+        return None;
+    }
+    Some(hex::encode(sig))
+}
+
+/// Forge a signature for `msg` without the private key, against a verifier
+/// that doesn't check the hash is right-justified.
+fn forge<H: DigestInfo>(msg: &[u8], public: &PublicKey) -> Option<Vec<u8>> {
+    if public.e != BigUint::from(3u64) {
+        return None;
+    }
+
+    // Put a minimal valid-looking prefix at the top of the block, and leave
+    // the rest as garbage to absorb the error from rounding the cube root.
+    let size = public.size();
+    let prefix = [&[0x00, 0x01, 0xff, 0x00], &H::digest_info(msg)[..]].concat();
+    let garbage = size.checked_sub(prefix.len())?;
+    let low = BigUint::from_bytes_be(&[&prefix[..], &vec![0x00; garbage]].concat());
+    let high = BigUint::from_bytes_be(&[&prefix[..], &vec![0xff; garbage]].concat());
+
+    // Round the cube root up so that cubing it lands at or above the prefix
+    let mut root = low.nth_root(3);
+    if root.pow(3) < low {
+        root = &root + 1;
+    }
+    (root.pow(3) <= high).then(|| root.to_bytes_be_padded(size))
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(42, solve, "hi mom").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cpr::hash::sha256::Sha256;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        assert!(solve("hi mom").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sha256() {
+        // A SHA-256 DigestInfo is longer, so it needs a bigger modulus to
+        // leave room for the garbage.
+        let msg = b"hi mom";
+        let (public, _) = rsa::keygen(1024, 3);
+        assert_eq!(forge::<Sha256>(msg, &public), None);
+
+        let (public, private) = rsa::keygen(2048, 3);
+        let sig = forge::<Sha256>(msg, &public).unwrap();
+        assert!(public.verify_sloppy::<Sha256>(msg, &sig));
+        assert!(!public.verify::<Sha256>(msg, &sig));
+
+        // The real thing passes both
+        let sig = private.sign::<Sha256>(msg).unwrap();
+        assert!(public.verify_sloppy::<Sha256>(msg, &sig));
+        assert!(public.verify::<Sha256>(msg, &sig));
+    }
+}
@@ -0,0 +1,84 @@
+// DSA key recovery from nonce
+use cpr::{
+    bigint::BigUint,
+    dsa::{self, Params, Signature},
+};
+use std::error::Error;
+
+const Y: &str = "\
+    84ad4719d044495496a3201c8ff484feb45b962e7302e56a392aee4abab3e4bd\
+    ebf2955b4736012f21a08084056b19bcd7fee56048e004e44984e2f411788efd\
+    c837a0d2e5abb7b555039fd243ac01f0fb2ed1dec568280ce678e931868d23eb\
+    095fde9d3779191b8c0299d6e07bbb283e6633451e535c45513b2d33c99ea17";
+
+const R: &str = "548099063082341131477253921760299949438196259240";
+const S: &str = "857042759984254168557880549501802188789837994940";
+const FINGERPRINT: &str = "0954edd5e0afe5542a4adf012611a91912a3ec16";
+
+pub fn solve(input: &str) -> Option<String> {
+    let params = Params::challenge();
+    let sig = Signature {
+        r: R.parse().ok()?,
+        s: S.parse().ok()?,
+    };
+
+    // Make sure we're attacking the right thing
+    let y = BigUint::from_hex(Y)?;
+    if !params.verify(&y, input.as_bytes(), &sig) {
+        return None;
+    }
+
+    let x = attack(&params, input.as_bytes(), &sig, FINGERPRINT)?;
+    Some(dsa::fingerprint(&x))
+}
+
+/// Recover the private key behind a signature whose nonce was picked from
+/// 0..2^16, by trying every nonce until the key matches the fingerprint.
+fn attack(params: &Params, msg: &[u8], sig: &Signature, fingerprint: &str) -> Option<BigUint> {
+    let h = dsa::hash(msg);
+    (0..=u16::MAX as u64)
+        .filter_map(|k| params.private_from_nonce(&h, sig, &k.into()))
+        .find(|x| dsa::fingerprint(x) == fingerprint)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let input = "For those that envy a MC it can be hazardous to your health\n\
+                 So be friendly, a matter of life and death, just like a etch-a-sketch\n";
+    cpr::solve!(43, solve, input).ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "For those that envy a MC it can be hazardous to your health\n\
+                           So be friendly, a matter of life and death, just like a etch-a-sketch\n";
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            dsa::hash(EXAMPLE.as_bytes()).to_hex(),
+            "d2d0714f014a9784047eaeccf956520045c45265"
+        );
+        let want = Some(FINGERPRINT.into());
+        let got = solve(EXAMPLE);
+        assert_eq!(want, got);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fresh_key() {
+        let params = Params::challenge();
+        let keys = params.keypair();
+        let k = BigUint::from(rand::random::<u16>() as u64 | 1);
+        let sig = params.sign_with_k(&keys.private, b"hi mom", &k);
+        assert!(params.verify(&keys.public, b"hi mom", &sig));
+
+        let fingerprint = dsa::fingerprint(&keys.private);
+        assert_eq!(
+            attack(&params, b"hi mom", &sig, &fingerprint),
+            Some(keys.private)
+        );
+    }
+}
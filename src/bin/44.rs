@@ -0,0 +1,118 @@
+// DSA nonce recovery from repeated nonce
+use cpr::{
+    bigint::BigUint,
+    dsa::{self, Params, Signature},
+};
+use std::error::Error;
+
+const FINGERPRINT: &str = "ca8f6f7c66fa362d40760d135b763eb8527d3d52";
+
+pub fn solve(input: &str) -> Option<String> {
+    let signed = parse(input)?;
+    let x = attack(&Params::challenge(), &signed)?;
+    Some(dsa::fingerprint(&x))
+}
+
+/// Parse blocks of `msg:`, `s:`, `r:` and `m:` lines into message hashes and
+/// their signatures.
+fn parse(input: &str) -> Option<Vec<(BigUint, Signature)>> {
+    let values = input
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .filter(|(key, _)| *key != "msg")
+        .map(|(_, value)| value.trim())
+        .collect::<Vec<_>>();
+    values
+        .chunks(3)
+        .map(|chunk| match chunk {
+            [s, r, m] => Some((
+                BigUint::from_hex(m)?,
+                Signature {
+                    r: r.parse().ok()?,
+                    s: s.parse().ok()?,
+                },
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Find two signatures that share a nonce (and therefore an r) and solve for
+/// the private key: k = (m1 - m2) / (s1 - s2) mod q.
+fn attack(params: &Params, signed: &[(BigUint, Signature)]) -> Option<BigUint> {
+    let q = &params.q;
+    for (i, (m1, sig1)) in signed.iter().enumerate() {
+        for (m2, sig2) in &signed[i + 1..] {
+            if sig1.r != sig2.r || sig1.s == sig2.s {
+                continue;
+            }
+            let dm = &(&(m1 % q) + q) - &(m2 % q);
+            let ds = &(&(&sig1.s + q) - &sig2.s) % q;
+            let k = &(&dm * &ds.modinv(q)?) % q;
+
+            // Both signatures should agree on the key
+            let x = params.private_from_nonce(m1, sig1, &k)?;
+            if params.private_from_nonce(m2, sig2, &k)? == x {
+                return Some(x);
+            }
+        }
+    }
+    None
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let input = &cpr::read_data(44, false)?;
+    let got = cpr::solve!(44, solve, input).ok_or("no solution")?;
+    if got != FINGERPRINT {
+        return Err("wrong key".into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        // Sign a handful of messages, reusing a nonce for a couple of them, and
+        // write them out in the same format as the challenge data.
+        let params = Params::challenge();
+        let keys = params.keypair();
+        let reused = BigUint::random_range(&BigUint::one(), &params.q);
+        let mut input = String::new();
+        for (i, msg) in ["foo", "bar", "baz", "qux", "quux", "corge"]
+            .iter()
+            .enumerate()
+        {
+            let sig = match i {
+                1 | 4 => params.sign_with_k(&keys.private, msg.as_bytes(), &reused),
+                _ => keys.sign(&params, msg.as_bytes()),
+            };
+            input.push_str(&format!(
+                "msg: {}\ns: {}\nr: {}\nm: {}\n",
+                msg,
+                sig.s,
+                sig.r,
+                dsa::hash(msg.as_bytes()).to_hex()
+            ));
+        }
+
+        let want = Some(dsa::fingerprint(&keys.private));
+        let got = solve(&input);
+        assert_eq!(want, got);
+        Ok(())
+    }
+
+    // Needs data/44.txt, which isn't checked in yet; fetch it from
+    // https://cryptopals.com/static/challenge-data/44.txt
+    #[test]
+    #[ignore]
+    fn test_data() -> Result<(), Box<dyn Error>> {
+        let input = &cpr::read_data(44, false)?;
+        let want = Some(FINGERPRINT.into());
+        let got = solve(input);
+        assert_eq!(want, got);
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+// DSA parameter tampering
+use cpr::{
+    bigint::BigUint,
+    dsa::{Keypair, Params, Signature},
+};
+use std::error::Error;
+
+pub fn solve(input: &str) -> Option<String> {
+    let params = Params::challenge();
+    let keys = params.keypair();
+    let msgs = input.lines().collect::<Vec<_>>();
+
+    // With g = 0, a signature over one message verifies for all of them, as
+    // long as the verifier doesn't check r
+    let zero = Params::new(params.p.clone(), params.q.clone(), BigUint::zero());
+    let sig = forge_zero(&params, &keys, msgs.first()?.as_bytes());
+    if !msgs
+        .iter()
+        .all(|msg| zero.verify_unchecked(&keys.public, msg.as_bytes(), &sig))
+    {
+        return None;
+    }
+
+    // With g = p + 1, one signature is good for every message, even with the
+    // range checks
+    let tampered = Params::new(params.p.clone(), params.q.clone(), &params.p + 1);
+    let sig = forge_magic(&tampered, &keys.public);
+    msgs.iter()
+        .all(|msg| tampered.verify(&keys.public, msg.as_bytes(), &sig))
+        .then(|| msgs.join("\n"))
+}
+
+/// Sign with g = 0. Every r comes out as 0, and so does every v computed by a
+/// verifier that doesn't reject r = 0.
+fn forge_zero(params: &Params, keys: &Keypair, msg: &[u8]) -> Signature {
+    let tampered = Params::new(params.p.clone(), params.q.clone(), BigUint::zero());
+    let k = BigUint::random_range(&BigUint::one(), &params.q);
+    tampered.sign_with_k(&keys.private, msg, &k)
+}
+
+/// With g = p + 1, g^anything is 1 mod p, so the verifier computes v = y^z for
+/// r = (y^z mod p) mod q and s = r / z mod q, no matter the message.
+fn forge_magic(params: &Params, public: &BigUint) -> Signature {
+    let q = &params.q;
+    let z = BigUint::random_range(&BigUint::one(), q);
+    let r = &public.modpow(&z, &params.p) % q;
+    let s = &(&r * &z.modinv(q).unwrap()) % q;
+    Signature { r, s }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(45, solve, "Hello, world\nGoodbye, world").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let input = "Hello, world\nGoodbye, world";
+        let want = Some(input.into());
+        let got = solve(input);
+        assert_eq!(want, got);
+        Ok(())
+    }
+
+    #[test]
+    fn test_zero_g() {
+        let params = Params::challenge();
+        let keys = params.keypair();
+        let tampered = Params::new(params.p.clone(), params.q.clone(), BigUint::zero());
+        let sig = forge_zero(&params, &keys, b"Hello, world");
+        assert!(sig.r.is_zero());
+
+        // A verifier that checks 0 < r < q isn't fooled, one that doesn't is
+        for msg in [&b"Hello, world"[..], b"Goodbye, world"] {
+            assert!(tampered.verify_unchecked(&keys.public, msg, &sig));
+            assert!(!tampered.verify(&keys.public, msg, &sig));
+        }
+    }
+}
@@ -0,0 +1,109 @@
+// RSA parity oracle
+use cpr::{
+    bigint::BigUint,
+    rsa::{self, PublicKey},
+    utils,
+};
+use std::error::Error;
+
+pub fn solve(input: &str) -> Option<String> {
+    let (public, oracle) = make_oracle();
+    let m = BigUint::from_bytes_be(&base64::decode(input.trim()).ok()?);
+    let c = public.encrypt(&m);
+
+    // Only show off when running for real
+    let pt = attack(&c, &public, &oracle, !cfg!(test));
+    String::from_utf8(pt.to_bytes_be()).ok()
+}
+
+/// Recover the plaintext one bit at a time: doubling the plaintext wraps the
+/// modulus (making it odd) exactly when it was in the upper half of its
+/// current range.
+fn attack(
+    c: &BigUint,
+    public: &PublicKey,
+    oracle: &impl Fn(&BigUint) -> bool,
+    hollywood: bool,
+) -> BigUint {
+    let n = &public.n;
+    let double = public.encrypt(&2u64.into());
+
+    // Track the bounds as the exact fractions n * lo / 2^i and n * hi / 2^i,
+    // so that there's no rounding error to mess up the last few bits.
+    let (mut lo, mut hi) = (BigUint::zero(), BigUint::one());
+    let mut c = c.clone();
+    for i in 1..=n.bits() {
+        c = &(&c * &double) % n;
+        let mid = &lo + &hi;
+        lo = &lo << 1;
+        hi = &hi << 1;
+        if oracle(&c) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+
+        if hollywood {
+            let upper = ceil_div(&(n * &hi), i) - 1u64;
+            eprintln!(
+                "{}{}{}",
+                cpr::GREY,
+                utils::utf8_decode(&upper.to_bytes_be()),
+                cpr::RESET
+            );
+        }
+    }
+
+    // The plaintext sits strictly below the upper bound, which is now less
+    // than one away from the lower bound.
+    let i = n.bits();
+    &ceil_div(&(n * &hi), i) - 1u64
+}
+
+/// ceil(x / 2^i)
+fn ceil_div(x: &BigUint, i: usize) -> BigUint {
+    let denominator = BigUint::one() << i;
+    &(&(x + &denominator) - 1u64) >> i
+}
+
+/// A server that decrypts anything, but only tells you whether the plaintext
+/// is even.
+fn make_oracle() -> (PublicKey, impl Fn(&BigUint) -> bool) {
+    let (public, private) = rsa::keygen(1024, 65537);
+    (public, move |c: &BigUint| private.decrypt(c).is_even())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let input = "VGhhdCdzIHdoeSBJIGZvdW5kIHlvdSBkb24ndCBwbGF5IGFyb3VuZCB3aXRoIHRoZSBGdW5reSBDb2xkIE1lZGluYQ==";
+    cpr::solve!(46, solve, input).ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let input = "VGhhdCdzIHdoeSBJIGZvdW5kIHlvdSBkb24ndCBwbGF5IGFyb3VuZCB3aXRoIHRoZSBGdW5reSBDb2xkIE1lZGluYQ==";
+        let want =
+            Some("That's why I found you don't play around with the Funky Cold Medina".into());
+        let got = solve(input);
+        assert_eq!(want, got);
+        Ok(())
+    }
+
+    #[test]
+    fn test_edges() {
+        let (public, oracle) = make_oracle();
+        for m in [
+            BigUint::zero(),
+            BigUint::one(),
+            &public.n - 1u64,
+            BigUint::random_below(&public.n),
+        ] {
+            let c = public.encrypt(&m);
+            assert_eq!(attack(&c, &public, &oracle, false), m);
+        }
+    }
+}
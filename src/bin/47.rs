@@ -0,0 +1,56 @@
+// Bleichenbacher's PKCS 1.5 Padding Oracle (Simple Case)
+use cpr::{
+    bigint::BigUint,
+    rsa::{self, PublicKey},
+};
+use std::error::Error;
+
+pub fn solve(input: &str) -> Option<String> {
+    let (public, oracle) = make_oracle(256);
+    let em = rsa::pkcs1_v15_pad(input.trim().as_bytes(), public.size())?;
+    let c = public.encrypt(&BigUint::from_bytes_be(&em));
+
+    let (m, queries) = rsa::bleichenbacher(&c, &public, oracle)?;
+    eprintln!("{}=> {} oracle queries{}", cpr::GREY, queries, cpr::RESET);
+    let pt = rsa::pkcs1_v15_unpad(&m.to_bytes_be_padded(public.size()))?;
+    String::from_utf8(pt).ok()
+}
+
+/// A server that decrypts anything, but only tells you whether the plaintext
+/// is PKCS#1 v1.5 conforming (i.e. starts with `00 02`).
+fn make_oracle(bits: usize) -> (PublicKey, impl Fn(&BigUint) -> bool) {
+    let (public, private) = rsa::keygen(bits, 3);
+    let size = public.size();
+    (public, move |c: &BigUint| {
+        private
+            .decrypt(c)
+            .to_bytes_be_padded(size)
+            .starts_with(&[0x00, 0x02])
+    })
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(47, solve, "kick it, CC").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let want = Some("kick it, CC".into());
+        let got = solve("kick it, CC");
+        assert_eq!(want, got);
+        Ok(())
+    }
+
+    #[test]
+    fn test_oracle() {
+        let (public, oracle) = make_oracle(256);
+        let em = rsa::pkcs1_v15_pad(b"kick it, CC", public.size()).unwrap();
+        assert!(oracle(&public.encrypt(&BigUint::from_bytes_be(&em))));
+        assert!(!oracle(&public.encrypt(&BigUint::from(0x0002u64))));
+    }
+}
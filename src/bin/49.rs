@@ -0,0 +1,228 @@
+// CBC-MAC Message Forgery
+use cpr::{mac, utils};
+use std::error::Error;
+
+const VICTIM: u32 = 2;
+const ATTACKER: u32 = 3;
+const AMOUNT: u64 = 1_000_000;
+
+pub fn solve(_input: &str) -> Option<String> {
+    let server = Server::new();
+    let attacker = server.client(ATTACKER);
+
+    // Attacker-controlled IV: sign a transfer to ourselves, then move the
+    // money out of someone else's account instead
+    let token = attacker.v1(ATTACKER, AMOUNT);
+    let forged = forge_v1(&token, VICTIM)?;
+    let v1 = server.v1(&forged)?;
+
+    // Fixed IV: extend a transfer the victim made with one of our own. Our
+    // first block gets mangled in the process, so lead with a throwaway.
+    let captured = server.client(VICTIM).v2(&[(4, 100), (5, 50)]);
+    let forged = forge_v2(
+        &captured,
+        &attacker.v2(&[(ATTACKER, AMOUNT), (ATTACKER, AMOUNT)]),
+    );
+    let v2 = server.v2(&forged)?;
+
+    Some(
+        [v1].iter()
+            .chain(v2.iter())
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+/// Rewrite the `from=` in the first block of a signed request, fixing up the
+/// IV so that the first block still encrypts to the same thing.
+fn forge_v1(token: &[u8], from: u32) -> Option<Vec<u8>> {
+    let (msg, iv, mac) = split_v1(token)?;
+    let mut forged = msg.to_vec();
+    let old = format!("from={}&", parse_from(msg)?);
+    let new = format!("from={}&", from);
+    if old.len() != new.len() || old.len() > 16 {
+        return None;
+    }
+    forged[..new.len()].copy_from_slice(new.as_bytes());
+    let iv = utils::xor(&utils::xor(iv, &msg[..16]), &forged[..16]);
+    Some([&forged[..], &iv, mac].concat())
+}
+
+/// Glue a request of our own onto the end of a captured one. After the
+/// captured (padded) message, the CBC state is its MAC, so XORing that into
+/// our first block puts the chain right back where our own MAC started.
+fn forge_v2(captured: &[u8], ours: &[u8]) -> Vec<u8> {
+    let (msg, _) = captured.split_at(captured.len() - 16);
+    let captured_mac = &captured[captured.len() - 16..];
+    let (ours, mac) = ours.split_at(ours.len() - 16);
+
+    let mut glued = utils::pkcs7_pad(msg, 16);
+    glued.extend(utils::xor(&ours[..16], captured_mac));
+    glued.extend(&ours[16..]);
+    [&glued[..], mac].concat()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Transfer {
+    from: u32,
+    to: u32,
+    amount: u64,
+}
+
+impl std::fmt::Display for Transfer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} -> {}: {}", self.from, self.to, self.amount)
+    }
+}
+
+/// The API server, which trusts any request with a valid MAC.
+struct Server {
+    key: Vec<u8>,
+}
+
+impl Server {
+    fn new() -> Self {
+        Self {
+            key: utils::rand_bytes(16),
+        }
+    }
+
+    /// The web client for a logged-in user, which shares the server's key.
+    fn client(&self, user: u32) -> Client {
+        Client {
+            key: self.key.clone(),
+            user,
+        }
+    }
+
+    /// `from=#{from_id}&to=#{to_id}&amount=#{amount} || IV || MAC`
+    fn v1(&self, request: &[u8]) -> Option<Transfer> {
+        let (msg, iv, mac) = split_v1(request)?;
+        if !mac::constant_time_eq(&mac::cbc_mac(msg, &self.key, iv), mac) {
+            return None;
+        }
+        let params = parse_params(msg);
+        Some(Transfer {
+            from: parse_from(msg)?,
+            to: params.iter().find(|(k, _)| k == "to")?.1.parse().ok()?,
+            amount: params.iter().find(|(k, _)| k == "amount")?.1.parse().ok()?,
+        })
+    }
+
+    /// `from=#{from_id}&tx_list=#{to:amount(;to:amount)*} || MAC`, with a
+    /// fixed IV of zero. Transactions that don't parse are skipped.
+    fn v2(&self, request: &[u8]) -> Option<Vec<Transfer>> {
+        let (msg, mac) = request.split_at(request.len().checked_sub(16)?);
+        if !mac::constant_time_eq(&mac::cbc_mac(msg, &self.key, &[0; 16]), mac) {
+            return None;
+        }
+        let from = parse_from(msg)?;
+        let start = find(msg, b"&tx_list=")? + b"&tx_list=".len();
+        Some(
+            msg[start..]
+                .split(|&b| b == b';')
+                .filter_map(|tx| {
+                    let (to, amount) = std::str::from_utf8(tx).ok()?.split_once(':')?;
+                    Some(Transfer {
+                        from,
+                        to: to.parse().ok()?,
+                        amount: amount.parse().ok()?,
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+/// The web client, which will only sign transfers out of its own account.
+struct Client {
+    key: Vec<u8>,
+    user: u32,
+}
+
+impl Client {
+    fn v1(&self, to: u32, amount: u64) -> Vec<u8> {
+        let msg = format!("from={}&to={}&amount={}", self.user, to, amount);
+        let iv = utils::rand_bytes(16);
+        let mac = mac::cbc_mac(msg.as_bytes(), &self.key, &iv);
+        [msg.as_bytes(), &iv, &mac].concat()
+    }
+
+    fn v2(&self, txs: &[(u32, u64)]) -> Vec<u8> {
+        let txs = txs
+            .iter()
+            .map(|(to, amount)| format!("{}:{}", to, amount))
+            .collect::<Vec<_>>()
+            .join(";");
+        let msg = format!("from={}&tx_list={}", self.user, txs);
+        let mac = mac::cbc_mac(msg.as_bytes(), &self.key, &[0; 16]);
+        [msg.as_bytes(), &mac].concat()
+    }
+}
+
+fn split_v1(request: &[u8]) -> Option<(&[u8], &[u8], &[u8])> {
+    let (msg, rest) = request.split_at(request.len().checked_sub(32)?);
+    let (iv, mac) = rest.split_at(16);
+    (msg.len() >= 16).then_some((msg, iv, mac))
+}
+
+fn parse_params(msg: &[u8]) -> Vec<(String, String)> {
+    utils::utf8_decode(msg)
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn parse_from(msg: &[u8]) -> Option<u32> {
+    let end = msg.iter().position(|&b| b == b'&')?;
+    std::str::from_utf8(msg[..end].strip_prefix(b"from=")?)
+        .ok()?
+        .parse()
+        .ok()
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(49, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        // The victim's last transfer runs into the padding and glue block,
+        // and gets skipped along with them
+        let want = Some(["2 -> 3: 1000000", "2 -> 4: 100", "2 -> 3: 1000000"].join("\n"));
+        let got = solve("");
+        assert_eq!(want, got);
+        Ok(())
+    }
+
+    #[test]
+    fn test_server() {
+        let server = Server::new();
+        let client = server.client(VICTIM);
+        let want = Transfer {
+            from: VICTIM,
+            to: 4,
+            amount: 100,
+        };
+        let mut token = client.v1(4, 100);
+        assert_eq!(server.v1(&token), Some(want));
+        token[0] ^= 1;
+        assert_eq!(server.v1(&token), None);
+
+        let mut token = client.v2(&[(4, 100), (5, 50)]);
+        assert_eq!(server.v2(&token).map(|txs| txs.len()), Some(2));
+        token[0] ^= 1;
+        assert_eq!(server.v2(&token), None);
+    }
+}
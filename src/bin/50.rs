@@ -0,0 +1,87 @@
+// Hashing with CBC-MAC
+use cpr::{mac, utils};
+use rand::Rng;
+use std::error::Error;
+
+const KEY: &[u8] = b"YELLOW SUBMARINE";
+const IV: [u8; 16] = [0; 16];
+const ORIGINAL: &str = "alert('MZA who was that?');\n";
+
+pub fn solve(input: &str) -> Option<String> {
+    let forged = forge(
+        input.trim().as_bytes(),
+        ORIGINAL.as_bytes(),
+        KEY,
+        &IV,
+        is_comment,
+    )?;
+    String::from_utf8(forged).ok()
+}
+
+/// Forge a message that starts with `prefix` and has the same CBC-MAC as
+/// `original`: pad out the prefix, then add a glue block that takes the CBC
+/// state right back to where the original's first block leaves it.
+///
+/// The padding and glue are random-looking, so keep retrying with different
+/// filler until `accept` is happy with everything after the prefix.
+fn forge(
+    prefix: &[u8],
+    original: &[u8],
+    key: &[u8],
+    iv: &[u8],
+    accept: impl Fn(&[u8]) -> bool,
+) -> Option<Vec<u8>> {
+    let block_size = key.len();
+    let first = original.get(..block_size)?;
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..1_000_000 {
+        let filler_len = rng.gen_range(0..block_size);
+        let filler = (0..filler_len)
+            .map(|_| rng.gen_range(b' '..=b'~'))
+            .collect::<Vec<_>>();
+        let padded = utils::pkcs7_pad(&[prefix, &filler].concat(), block_size);
+        let state = mac::cbc_mac(&padded, key, iv);
+        let glue = utils::xor(&state, first);
+
+        let forged = [&padded[..], &glue, &original[block_size..]].concat();
+        if accept(&forged[prefix.len()..]) {
+            return Some(forged);
+        }
+    }
+    None
+}
+
+/// Whether this is the rest of a single-line JavaScript comment: valid UTF-8,
+/// with no line breaks until the very end.
+fn is_comment(bytes: &[u8]) -> bool {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => !s
+            .trim_end_matches('\n')
+            .contains(['\n', '\r', '\u{2028}', '\u{2029}']),
+        Err(_) => false,
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(50, solve, "alert('Ayo, the Wu is back!');//").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let prefix = "alert('Ayo, the Wu is back!');//";
+        let got = solve(prefix).ok_or("no forgery")?;
+        assert!(got.starts_with(prefix));
+        assert!(got.ends_with('\n'));
+        assert_eq!(
+            hex::encode(mac::cbc_mac(got.as_bytes(), KEY, &IV)),
+            "296b8d7cb78a243dda4d0a61d33bbdd1"
+        );
+        Ok(())
+    }
+}
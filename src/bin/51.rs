@@ -0,0 +1,119 @@
+// Compression Ratio Side-Channel Attacks
+use cpr::{
+    deflate,
+    utils::{self, Op},
+};
+use std::error::Error;
+
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=\n";
+const KNOWN: &[u8] = b"sessionid=";
+
+pub fn solve(input: &str) -> Option<String> {
+    let secret = input.trim();
+    let stream = attack(make_oracle(secret, Mode::Stream))?;
+    let block = attack(make_oracle(secret, Mode::Block))?;
+    (stream == block).then_some(stream)
+}
+
+/// Recover the session id one character at a time. The right guess extends
+/// the back-reference to the real cookie, so the request compresses a little
+/// better than for any other guess.
+///
+/// That little bit can get lost in rounding: to a whole byte with a stream
+/// cipher, and to a whole block with a block cipher. So prepend some junk that
+/// the compressor can't do anything with, one byte (and nine bits) at a time,
+/// until the length for one guess ticks over and the rest don't.
+fn attack(oracle: impl Fn(&[u8]) -> usize) -> Option<String> {
+    let mut known = KNOWN.to_vec();
+    loop {
+        let mut candidates = ALPHABET.to_vec();
+        for pad in 0..=64 {
+            let lengths = candidates
+                .iter()
+                .map(|&c| oracle(&[&junk(pad), &known[..], &[c]].concat()))
+                .collect::<Vec<_>>();
+            let min = *lengths.iter().min()?;
+            candidates = candidates
+                .iter()
+                .zip(lengths)
+                .filter(|&(_, len)| len == min)
+                .map(|(&c, _)| c)
+                .collect();
+            if candidates.len() == 1 {
+                break;
+            }
+        }
+
+        match candidates[..] {
+            [b'\n'] => break,
+            [c] => known.push(c),
+            _ => return None,
+        }
+        if known.len() > 128 {
+            return None;
+        }
+    }
+    String::from_utf8(known[KNOWN.len()..].to_vec()).ok()
+}
+
+/// Bytes that don't repeat and aren't in the alphabet, so they always get
+/// encoded as (9-bit) literals.
+fn junk(len: usize) -> Vec<u8> {
+    (0..len).map(|i| 0x90 + i as u8).collect()
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Mode {
+    Stream,
+    Block,
+}
+
+/// Compress and encrypt a request with the secret cookie in it, under a fresh
+/// key each time, and leak only the length.
+fn make_oracle(secret: &str, mode: Mode) -> impl Fn(&[u8]) -> usize {
+    let secret = secret.to_string();
+    move |payload: &[u8]| {
+        let request = [
+            format!(
+                "POST / HTTP/1.1\nHost: hapless.com\nCookie: sessionid={}\nContent-Length: {}\n",
+                secret,
+                payload.len()
+            )
+            .as_bytes(),
+            payload,
+        ]
+        .concat();
+        let compressed = deflate::compress(&request);
+        let key = utils::rand_bytes(16);
+        match mode {
+            Mode::Stream => utils::ctr(&compressed, &key, rand::random()).len(),
+            Mode::Block => utils::cbc(&compressed, &key, &utils::rand_bytes(16), Op::Encrypt).len(),
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(51, solve, "TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let input = "TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=";
+        let want = Some(input.into());
+        let got = solve(input);
+        assert_eq!(want, got);
+        Ok(())
+    }
+
+    #[test]
+    fn test_random_secret() {
+        let secret = base64::encode(utils::rand_bytes(24));
+        let got = attack(make_oracle(&secret, Mode::Block));
+        assert_eq!(got, Some(secret));
+    }
+}
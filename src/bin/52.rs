@@ -0,0 +1,87 @@
+// Iterated Hash Function Multicollisions
+use cpr::md::{self, MerkleDamgard};
+use std::{collections::HashMap, error::Error};
+
+pub fn solve(_input: &str) -> Option<String> {
+    let (f, g) = (MerkleDamgard::toy(2), MerkleDamgard::toy(3));
+    let (m1, m2) = attack(&f, &g)?;
+    eprintln!(
+        "{}=> f: {} calls, g: {} calls{}",
+        cpr::GREY,
+        f.calls(),
+        g.calls(),
+        cpr::RESET
+    );
+
+    let h1 = [f.digest(&m1), g.digest(&m1)].concat();
+    let h2 = [f.digest(&m2), g.digest(&m2)].concat();
+    (m1 != m2 && h1 == h2).then(|| hex::encode(h1))
+}
+
+/// Collide h(x) = f(x) || g(x), where g is the stronger of the two. Any
+/// collision in h has to be a collision in f, so generate 2^(b/2) of those
+/// for a b-bit g, and wait for the birthday paradox to find one in g too.
+/// If it doesn't, just add another stage and try again.
+fn attack(f: &MerkleDamgard, g: &MerkleDamgard) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mut stages = f.multicollision(f.iv(), g.state_size() * 8 / 2);
+    loop {
+        // Every message is the same length, so they all share a padding block
+        let pad = md::padding((stages.len() * md::BLOCK_SIZE) as u64);
+
+        // Walk the tree of messages one stage at a time, so that common
+        // prefixes only get hashed once.
+        let mut states = vec![(0, g.iv().to_vec())];
+        for (i, stage) in stages.iter().enumerate() {
+            states = states
+                .iter()
+                .flat_map(|(choice, state)| {
+                    stage
+                        .blocks
+                        .iter()
+                        .enumerate()
+                        .map(move |(bit, block)| (choice | bit << i, g.compress(state, block)))
+                })
+                .collect();
+        }
+
+        let mut seen = HashMap::new();
+        for (choice, state) in states {
+            let digest = g.iterate(&state, &pad);
+            if let Some(&other) = seen.get(&digest) {
+                return Some((md::choose(&stages, other), md::choose(&stages, choice)));
+            }
+            seen.insert(digest, choice);
+        }
+
+        let last = stages.last()?.state.clone();
+        stages.extend(f.multicollision(&last, 1));
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(52, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        assert!(solve("").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_calls() {
+        // A birthday attack on the whole 40-bit h would take around 2^20
+        // calls to each of f and g
+        let (f, g) = (MerkleDamgard::toy(2), MerkleDamgard::toy(3));
+        let (m1, m2) = attack(&f, &g).unwrap();
+        assert_ne!(m1, m2);
+        assert_eq!(f.digest(&m1), f.digest(&m2));
+        assert_eq!(g.digest(&m1), g.digest(&m2));
+        assert!(f.calls() + g.calls() < 1 << 20);
+    }
+}
@@ -0,0 +1,79 @@
+// Kelsey and Schneier's Expandable Messages
+use cpr::{
+    md::{self, MerkleDamgard},
+    utils,
+};
+use std::{collections::HashMap, error::Error};
+
+const K: usize = 10;
+
+pub fn solve(_input: &str) -> Option<String> {
+    let md = MerkleDamgard::toy(3);
+    let target = utils::rand_bytes((1 << K) * md::BLOCK_SIZE);
+    let forged = attack(&md, &target, K)?;
+
+    let digest = md.digest(&target);
+    (forged != target && forged.len() == target.len() && md.digest(&forged) == digest)
+        .then(|| hex::encode(digest))
+}
+
+/// Find a second preimage for a 2^k block message. Bridge from the end of an
+/// expandable message into one of the target's intermediate states, then
+/// stretch the expandable message so that the forgery comes out the same
+/// length as the target, and gets the same length padding.
+fn attack(md: &MerkleDamgard, target: &[u8], k: usize) -> Option<Vec<u8>> {
+    let blocks = target.chunks(md::BLOCK_SIZE).collect::<Vec<_>>();
+    let expandable = md.expandable(md.iv(), k);
+
+    // Map each intermediate state to how many blocks it took to get there.
+    // Bridging into the first k + 1 is no good, since the expandable message
+    // can't get any shorter than k.
+    let mut state = md.iv().to_vec();
+    let mut intermediate = HashMap::new();
+    for (i, block) in blocks.iter().enumerate() {
+        state = md.compress(&state, block);
+        if i > k {
+            intermediate.insert(state.clone(), i + 1);
+        }
+    }
+
+    let (bridge, end) = loop {
+        let bridge = utils::rand_bytes(md::BLOCK_SIZE);
+        if let Some(&end) = intermediate.get(&md.compress(&expandable.state, &bridge)) {
+            break (bridge, end);
+        }
+    };
+
+    let prefix = expandable.message(end - 1)?;
+    Some([&prefix[..], &bridge, &blocks[end..].concat()].concat())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(53, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        assert!(solve("").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_padding() {
+        // A message that isn't a whole number of blocks still works, as long
+        // as the forgery keeps its tail
+        let md = MerkleDamgard::toy(2);
+        let target = utils::rand_bytes((1 << 6) * md::BLOCK_SIZE + 5);
+        let whole = target.len() / md::BLOCK_SIZE * md::BLOCK_SIZE;
+        let forged = attack(&md, &target[..whole], 6).unwrap();
+        let forged = [&forged[..], &target[whole..]].concat();
+        assert_ne!(forged, target);
+        assert_eq!(forged.len(), target.len());
+        assert_eq!(md.digest(&forged), md.digest(&target));
+    }
+}
@@ -0,0 +1,104 @@
+// Kelsey and Kohno's Nostradamus Attack
+use cpr::{
+    md::{self, MerkleDamgard},
+    utils,
+};
+use std::error::Error;
+
+const K: usize = 8;
+/// How many blocks we leave for the message we don't know yet.
+const PREFIX_BLOCKS: usize = 6;
+
+pub fn solve(input: &str) -> Option<String> {
+    let md = MerkleDamgard::toy(2);
+
+    // Before the season: build the diamond and publish a prediction
+    let prediction = Prediction::new(&md, K, PREFIX_BLOCKS);
+    eprintln!(
+        "{}=> prediction: {}{}",
+        cpr::GREY,
+        hex::encode(&prediction.digest),
+        cpr::RESET
+    );
+
+    // After the season: herd the real results into the prediction
+    let msg = prediction.herd(&md, input.trim().as_bytes())?;
+    (md.digest(&msg) == prediction.digest).then(|| utils::utf8_decode(&msg))
+}
+
+/// A hash committed to ahead of time, along with the diamond that lets us
+/// make any (short enough) message hash to it.
+struct Prediction {
+    diamond: md::Diamond,
+    prefix_blocks: usize,
+    digest: Vec<u8>,
+}
+
+impl Prediction {
+    fn new(md: &MerkleDamgard, k: usize, prefix_blocks: usize) -> Self {
+        let diamond = md.diamond(k);
+
+        // The message will be the prefix, a linking block, and a path through
+        // the diamond, so we already know how long it'll be, and what padding
+        // it'll get.
+        let len = (prefix_blocks + 1 + k) * md::BLOCK_SIZE;
+        let digest = md.iterate(&diamond.state, &md::padding(len as u64));
+        Self {
+            diamond,
+            prefix_blocks,
+            digest,
+        }
+    }
+
+    /// Pad `prefix` out with spaces, then find a block that links it to one of
+    /// the diamond's leaves, and follow the path from there.
+    fn herd(&self, md: &MerkleDamgard, prefix: &[u8]) -> Option<Vec<u8>> {
+        let len = self.prefix_blocks * md::BLOCK_SIZE;
+        if prefix.len() > len {
+            return None;
+        }
+        let mut prefix = prefix.to_vec();
+        prefix.resize(len, b' ');
+
+        let state = md.iterate(md.iv(), &prefix);
+        let (link, leaf) = loop {
+            let link = utils::rand_bytes(md::BLOCK_SIZE);
+            if let Some(leaf) = self.diamond.leaf(&md.compress(&state, &link)) {
+                break (link, leaf);
+            }
+        };
+        Some([prefix, link, self.diamond.path(leaf)].concat())
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let input = "Red Sox 4, Cardinals 0\nRed Sox 6, Cardinals 2\nRed Sox 4, Cardinals 1\nRed Sox 3, Cardinals 0";
+    cpr::solve!(54, solve, input).ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let input = "Red Sox 4, Cardinals 0\nRed Sox 6, Cardinals 2\nRed Sox 4, Cardinals 1\nRed Sox 3, Cardinals 0";
+        let got = solve(input).ok_or("no solution")?;
+        assert!(got.starts_with(input));
+        Ok(())
+    }
+
+    #[test]
+    fn test_many_outcomes() {
+        // One prediction covers every outcome
+        let md = MerkleDamgard::toy(2);
+        let prediction = Prediction::new(&md, 6, 2);
+        for score in ["Cubs 3, Rays 1", "Rays 2, Cubs 0", "rained out"] {
+            let msg = prediction.herd(&md, score.as_bytes()).unwrap();
+            assert!(msg.starts_with(score.as_bytes()));
+            assert_eq!(md.digest(&msg), prediction.digest);
+        }
+        assert_eq!(prediction.herd(&md, &[b'x'; 33]), None);
+    }
+}
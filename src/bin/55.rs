@@ -0,0 +1,354 @@
+// MD4 Collisions
+use cpr::hash::md4::{self, INIT, K2};
+use std::error::Error;
+
+/// Where a sufficient condition wants a bit of a state variable to be.
+#[derive(Clone, Copy)]
+enum Cond {
+    Zero,
+    One,
+    /// Equal to the same bit of the previous state variable.
+    Prev,
+    /// Equal to the same bit of the state variable before the previous one.
+    PrevPrev,
+}
+
+use Cond::*;
+
+/// Sufficient conditions on the state after each of the first nineteen steps
+/// (a1, d1, c1, b1, a2, ..., b4, a5, d5, c5) for Wang's differential to hold,
+/// as (bit, condition) with bits numbered from 1 like in the paper.
+///
+/// See "Cryptanalysis of the Hash Functions MD4 and RIPEMD" (Wang et al.,
+/// 2005), table 6.
+const CONDITIONS: [&[(u32, Cond)]; 19] = [
+    // a1
+    &[(7, Prev)],
+    // d1
+    &[(7, Zero), (8, Prev), (11, Prev)],
+    // c1
+    &[(7, One), (8, One), (11, Zero), (26, Prev)],
+    // b1
+    &[(7, One), (8, Zero), (11, Zero), (26, Zero)],
+    // a2
+    &[(8, One), (11, One), (26, Zero), (14, Prev)],
+    // d2
+    &[
+        (14, Zero),
+        (19, Prev),
+        (20, Prev),
+        (21, Prev),
+        (22, Prev),
+        (26, One),
+    ],
+    // c2
+    &[
+        (13, Prev),
+        (14, Zero),
+        (15, Prev),
+        (19, Zero),
+        (20, Zero),
+        (21, One),
+        (22, Zero),
+    ],
+    // b2
+    &[
+        (13, One),
+        (14, One),
+        (15, Zero),
+        (17, Prev),
+        (19, Zero),
+        (20, Zero),
+        (21, Zero),
+        (22, Zero),
+    ],
+    // a3
+    &[
+        (13, One),
+        (14, One),
+        (15, One),
+        (17, Zero),
+        (19, Zero),
+        (20, Zero),
+        (21, Zero),
+        (23, Prev),
+        (22, One),
+        (26, Prev),
+    ],
+    // d3
+    &[
+        (13, One),
+        (14, One),
+        (15, One),
+        (17, Zero),
+        (20, Zero),
+        (21, One),
+        (22, One),
+        (23, Zero),
+        (26, One),
+        (30, Prev),
+    ],
+    // c3
+    &[
+        (17, One),
+        (20, Zero),
+        (21, Zero),
+        (22, Zero),
+        (23, Zero),
+        (26, Zero),
+        (30, One),
+        (32, Prev),
+    ],
+    // b3
+    &[
+        (20, Zero),
+        (21, One),
+        (22, One),
+        (23, Prev),
+        (26, One),
+        (30, Zero),
+        (32, Zero),
+    ],
+    // a4
+    &[
+        (23, Zero),
+        (26, Zero),
+        (27, Prev),
+        (29, Prev),
+        (30, One),
+        (32, Zero),
+    ],
+    // d4
+    &[
+        (23, Zero),
+        (26, Zero),
+        (27, One),
+        (29, One),
+        (30, Zero),
+        (32, One),
+    ],
+    // c4
+    &[
+        (19, Prev),
+        (23, One),
+        (26, One),
+        (27, Zero),
+        (29, Zero),
+        (30, Zero),
+    ],
+    // b4
+    &[(19, Zero), (26, One), (27, One), (29, One), (30, Zero)],
+    // a5: bit 19 against c4, the rest against b4
+    &[
+        (19, PrevPrev),
+        (26, Prev),
+        (27, Prev),
+        (29, Prev),
+        (32, Prev),
+    ],
+    // d5: bit 19 against a5, the rest against b4
+    &[
+        (19, Prev),
+        (26, PrevPrev),
+        (27, PrevPrev),
+        (29, PrevPrev),
+        (32, PrevPrev),
+    ],
+    // c5
+    &[(26, Prev), (27, Prev), (29, Prev), (30, Prev), (32, Prev)],
+];
+
+const ROUND1_SHIFTS: [u32; 4] = [3, 7, 11, 19];
+const ROUND2_SHIFTS: [u32; 4] = [3, 5, 9, 13];
+
+pub fn solve(_input: &str) -> Option<String> {
+    let (m1, m2) = attack(1 << 24)?;
+    (m1 != m2 && md4::digest(&m1) == md4::digest(&m2))
+        .then(|| format!("{}\n{}", hex::encode(m1), hex::encode(m2)))
+}
+
+/// Keep massaging random messages until one of them collides with its
+/// differential partner.
+fn attack(tries: usize) -> Option<(Vec<u8>, Vec<u8>)> {
+    (0..tries).find_map(|_| {
+        let words = massage(random_words());
+        let (m1, m2) = (to_block(&words), to_block(&partner(&words)));
+        let (mut s1, mut s2) = (INIT, INIT);
+        md4::compress(&mut s1, &m1);
+        md4::compress(&mut s2, &m2);
+        (s1 == s2).then_some((m1, m2))
+    })
+}
+
+/// The message with Wang's differences: M' = M + (2^31 at m1, 2^31 - 2^28 at
+/// m2, -2^16 at m12).
+fn partner(words: &[u32; 16]) -> [u32; 16] {
+    let mut other = *words;
+    other[1] = other[1].wrapping_add(1 << 31);
+    other[2] = other[2].wrapping_add((1 << 31) - (1 << 28));
+    other[12] = other[12].wrapping_sub(1 << 16);
+    other
+}
+
+/// Apply the message modifications: single-step for all of round 1, then
+/// multi-step for as much of the start of round 2 as we can manage without
+/// disturbing round 1.
+fn massage(mut m: [u32; 16]) -> [u32; 16] {
+    // The state variables in the order they're computed, starting with the
+    // IV as a0, d0, c0, b0.
+    let mut q = [0u32; 4 + 19];
+    q[..4].copy_from_slice(&[INIT[0], INIT[3], INIT[2], INIT[1]]);
+
+    // Round 1: compute each step, fix up its bits, then solve for the
+    // message word that produces the fixed value
+    for j in 0..16 {
+        let i = j + 4;
+        let v = round1(&q, i, m[j]);
+        q[i] = enforce(v, q[i - 1], q[i - 2], CONDITIONS[j]);
+        m[j] = unround1(&q, i);
+    }
+
+    // a5: flipping bit i of a1 nudges m0 by 2^(i - 3), which flips bit i of
+    // a5. Then re-solve m1..m4 so that d1, c1, b1 and a2 stay put.
+    q[20] = round2(&q, 20, m[0]);
+    correct(&mut q, &mut m, (20, 0), (4, 0), &[19, 26, 27, 29, 32]);
+
+    // d5: same trick with a2, which nudges m4 and flips bit i + 2 of d5
+    q[21] = round2(&q, 21, m[4]);
+    correct(&mut q, &mut m, (21, 4), (8, 2), &[17, 24, 25, 27, 30]);
+
+    // c5: a3 nudges m8 and flips bit i + 6 of c5. The only one of its bits
+    // that round 1 leaves free is 24, which covers c5,30.
+    q[22] = round2(&q, 22, m[8]);
+    correct(&mut q, &mut m, (22, 8), (12, 6), &[24]);
+    m
+}
+
+/// Fix the round 2 state variable `target` (computed from message word
+/// `word`) by flipping bits of round 1 variable `source`, whose bit i lands on
+/// bit i + `offset` of the target. `via` lists the source bits we're allowed
+/// to touch, numbered from 1.
+fn correct(
+    q: &mut [u32],
+    m: &mut [u32; 16],
+    (target, word): (usize, usize),
+    (source, offset): (usize, u32),
+    via: &[u32],
+) {
+    for &bit in via {
+        let want = enforce(
+            q[target],
+            q[target - 1],
+            q[target - 2],
+            CONDITIONS[target - 4],
+        );
+        let bit = bit - 1;
+        if (want ^ q[target]) >> ((bit + offset) % 32) & 1 == 0 {
+            continue;
+        }
+
+        // Move the round 1 variable, then re-solve the message words that
+        // feed it and the four steps after it
+        q[source] ^= 1 << bit;
+        for i in source..source + 5 {
+            m[i - 4] = unround1(q, i);
+        }
+        q[target] = round2(q, target, m[word]);
+    }
+}
+
+fn enforce(mut v: u32, prev: u32, prev_prev: u32, conditions: &[(u32, Cond)]) -> u32 {
+    for &(bit, cond) in conditions {
+        let mask = 1 << (bit - 1);
+        v = match cond {
+            Zero => v & !mask,
+            One => v | mask,
+            Prev => (v & !mask) | (prev & mask),
+            PrevPrev => (v & !mask) | (prev_prev & mask),
+        };
+    }
+    v
+}
+
+fn round1(q: &[u32], i: usize, m: u32) -> u32 {
+    q[i - 4]
+        .wrapping_add(md4::f(q[i - 1], q[i - 2], q[i - 3]))
+        .wrapping_add(m)
+        .rotate_left(ROUND1_SHIFTS[i % 4])
+}
+
+fn unround1(q: &[u32], i: usize) -> u32 {
+    q[i].rotate_right(ROUND1_SHIFTS[i % 4])
+        .wrapping_sub(q[i - 4])
+        .wrapping_sub(md4::f(q[i - 1], q[i - 2], q[i - 3]))
+}
+
+fn round2(q: &[u32], i: usize, m: u32) -> u32 {
+    q[i - 4]
+        .wrapping_add(md4::g(q[i - 1], q[i - 2], q[i - 3]))
+        .wrapping_add(m)
+        .wrapping_add(K2)
+        .rotate_left(ROUND2_SHIFTS[i % 4])
+}
+
+fn random_words() -> [u32; 16] {
+    let mut words = [0; 16];
+    words.iter_mut().for_each(|w| *w = rand::random());
+    words
+}
+
+fn to_block(words: &[u32; 16]) -> Vec<u8> {
+    words.iter().flat_map(|w| w.to_le_bytes()).collect()
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(55, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let got = solve("").ok_or("no collision")?;
+        let (m1, m2) = got.split_once('\n').ok_or("bad output")?;
+        let (m1, m2) = (hex::decode(m1)?, hex::decode(m2)?);
+        assert_eq!(m1.len(), 64);
+        assert_eq!(m2.len(), 64);
+        assert_ne!(m1, m2);
+        assert_eq!(md4::digest(&m1), md4::digest(&m2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_round1() {
+        // After massaging, every round 1 condition holds
+        let m = massage(random_words());
+        let mut q = [0u32; 4 + 16];
+        q[..4].copy_from_slice(&[INIT[0], INIT[3], INIT[2], INIT[1]]);
+        for j in 0..16 {
+            q[j + 4] = round1(&q, j + 4, m[j]);
+            assert_eq!(
+                q[j + 4],
+                enforce(q[j + 4], q[j + 3], q[j + 2], CONDITIONS[j])
+            );
+        }
+    }
+
+    #[test]
+    fn test_c5() {
+        // The c5 correction always lands: bit 30 matches d5
+        let m = massage(random_words());
+        let mut q = [0u32; 4 + 19];
+        q[..4].copy_from_slice(&[INIT[0], INIT[3], INIT[2], INIT[1]]);
+        for j in 0..16 {
+            q[j + 4] = round1(&q, j + 4, m[j]);
+        }
+        for (i, word) in [(20, 0), (21, 4), (22, 8)] {
+            q[i] = round2(&q, i, m[word]);
+        }
+        assert_eq!(q[22] >> 29 & 1, q[21] >> 29 & 1);
+    }
+}
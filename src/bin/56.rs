@@ -0,0 +1,96 @@
+// RC4 Single-Byte Biases
+use cpr::{rc4::Rc4, utils};
+use std::error::Error;
+
+/// Keystream byte 16 leans towards 240, and byte 32 towards 224.
+const BIASES: [(usize, u8); 2] = [(15, 240), (31, 224)];
+
+/// Enough to make the biases stand out reliably.
+const SAMPLES: usize = 1 << 24;
+
+pub fn solve(input: &str) -> Option<String> {
+    let cookie = base64::decode(input.trim()).ok()?;
+    let pt = attack(make_oracle(&cookie), SAMPLES)?;
+    String::from_utf8(pt).ok()
+}
+
+/// Recover the cookie byte by byte, by lining each byte up with one of the
+/// biased keystream positions (with a prefix of the right length) and taking
+/// the most common ciphertext byte there as "cookie byte XOR bias".
+fn attack(oracle: impl Fn(&[u8]) -> Vec<u8>, samples: usize) -> Option<Vec<u8>> {
+    let len = oracle(b"").len();
+    let (last, _) = BIASES[BIASES.len() - 1];
+    if len > last + 1 {
+        return None;
+    }
+
+    let mut cookie = vec![0; len];
+    for (i, byte) in cookie.iter_mut().enumerate() {
+        let (pos, bias) = *BIASES.iter().find(|(pos, _)| i <= *pos)?;
+        let prefix = vec![b'A'; pos - i];
+
+        let mut counts = [0usize; 256];
+        for _ in 0..samples {
+            counts[oracle(&prefix)[pos] as usize] += 1;
+        }
+        let (c, _) = counts.iter().enumerate().max_by_key(|(_, &n)| n)?;
+        *byte = c as u8 ^ bias;
+    }
+    Some(cookie)
+}
+
+/// Encrypt `request || cookie` under a fresh random 128-bit key every time.
+fn make_oracle(cookie: &[u8]) -> impl Fn(&[u8]) -> Vec<u8> {
+    let cookie = cookie.to_vec();
+    move |request: &[u8]| {
+        let mut rc4 = Rc4::new(&utils::rand_bytes(16));
+        utils::prng_stream_cipher(&[request, &cookie].concat(), &mut rc4)
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let input = "QkUgU1VSRSBUTyBEUklOSyBZT1VSIE9WQUxUSU5F";
+    cpr::solve!(56, solve, input).ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let input = "QkUgU1VSRSBUTyBEUklOSyBZT1VSIE9WQUxUSU5F";
+        let want = Some("BE SURE TO DRINK YOUR OVALTINE".into());
+        let got = solve(input);
+        assert_eq!(want, got);
+        Ok(())
+    }
+
+    #[test]
+    #[ignore]
+    fn test_short() {
+        // The biases are only a few percent, so even one byte takes a few
+        // million samples (a couple of minutes in a debug build)
+        let got = attack(make_oracle(b"!"), 1 << 22);
+        assert_eq!(got, Some(b"!".to_vec()));
+    }
+
+    #[test]
+    fn test_positions() {
+        // Swap in a keystream that always hits the biased values, to check
+        // that every byte of the cookie gets lined up with one of them
+        let cookie = b"BE SURE TO DRINK YOUR OVALTINE".to_vec();
+        let oracle = |request: &[u8]| {
+            let mut keystream = utils::rand_bytes(request.len() + cookie.len());
+            for (pos, bias) in BIASES {
+                if let Some(k) = keystream.get_mut(pos) {
+                    *k = bias;
+                }
+            }
+            utils::xor(&[request, &cookie].concat(), &keystream)
+        };
+        assert_eq!(attack(oracle, 4), Some(cookie.clone()));
+    }
+}
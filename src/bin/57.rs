@@ -0,0 +1,66 @@
+// Diffie-Hellman Revisited: Small Subgroup Confinement
+use cpr::{bigint::BigUint, dlog};
+use std::error::Error;
+
+const P: &str = "\
+    7199773997391911030609999317773941274322764333428698921736339643928346453700085358\
+    802973900485592910475480089726140708102474957429903531369589969318716771";
+const G: &str = "\
+    4565356397095740655436854503483826832136106141639563487732438195343690437606117828\
+    318042418238184896212352329118608100083187535033402010599512641674644143";
+const Q: &str = "236234353446506858198510045061214171961";
+
+pub fn solve(_input: &str) -> Option<String> {
+    let (p, g, q) = params();
+    let (public, oracle) = make_oracle(&p, &g, &q);
+
+    // Bob's key is less than q, so once we know it mod something bigger than
+    // q, we know it outright
+    let j = &(&p - 1) / &q;
+    let (x, _) = dlog::subgroup_confinement(&p, &j, &q, oracle)?;
+    (g.modpow(&x, &p) == public).then(|| x.to_string())
+}
+
+fn params() -> (BigUint, BigUint, BigUint) {
+    (P.parse().unwrap(), G.parse().unwrap(), Q.parse().unwrap())
+}
+
+/// Bob, who takes any public key he's given at face value and sends back a
+/// message MAC'd under the resulting shared secret.
+fn make_oracle(
+    p: &BigUint,
+    g: &BigUint,
+    q: &BigUint,
+) -> (BigUint, impl Fn(&BigUint) -> (Vec<u8>, Vec<u8>)) {
+    let private = BigUint::random_range(&BigUint::one(), q);
+    let public = g.modpow(&private, p);
+    let p = p.clone();
+    (public, move |h: &BigUint| {
+        let secret = h.modpow(&private, &p);
+        let msg = dlog::BOB_MESSAGE.to_vec();
+        let mac = dlog::mac(&secret, &msg);
+        (msg, mac)
+    })
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(57, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        assert!(solve("").is_some());
+    }
+
+    #[test]
+    fn test_params() {
+        let (p, g, q) = params();
+        assert!(g.modpow(&q, &p).is_one());
+        assert!((&(&p - 1) % &q).is_zero());
+    }
+}
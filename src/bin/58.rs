@@ -0,0 +1,83 @@
+// Pollard's Method for Catching Kangaroos
+use cpr::{bigint::BigUint, dlog};
+use std::error::Error;
+
+const P: &str = "\
+    1147037487492527565811666350723216140208665025845389627453499167689899926264158151\
+    9101074740642369848233294239851519212341844337347119899874391456329785623";
+const G: &str = "\
+    6229523353339612969781592660847410858898813587384599399782901799360636355667402585\
+    55167783009058567397963466103140082647486611657350811560630587013183357";
+const Q: &str = "335062023296420808191071248367701059461";
+
+pub fn solve(_input: &str) -> Option<String> {
+    let (p, g, q) = params();
+    let (public, oracle) = make_oracle(&p, &g, &q);
+    let x = attack(&p, &g, &q, &public, oracle)?;
+    (g.modpow(&x, &p) == public).then(|| x.to_string())
+}
+
+/// Get as much of Bob's key as the small subgroups will give up, then catch
+/// the rest with a kangaroo.
+fn attack(
+    p: &BigUint,
+    g: &BigUint,
+    q: &BigUint,
+    y: &BigUint,
+    oracle: impl Fn(&BigUint) -> (Vec<u8>, Vec<u8>),
+) -> Option<BigUint> {
+    let j = &(p - 1) / q;
+    let (n, r) = dlog::subgroup_confinement(p, &j, q, oracle)?;
+
+    // x = n + m * r for some m in [0, (q - 1) / r], so y * g^-n = (g^r)^m
+    let g_r = g.modpow(&r, p);
+    let y_r = &(y * &g.modpow(&(q - &(&n % q)), p)) % p;
+    let m = dlog::kangaroo(&g_r, &y_r, p, &BigUint::zero(), &(&(q - 1) / &r))?;
+    Some(&n + &(&m * &r))
+}
+
+fn params() -> (BigUint, BigUint, BigUint) {
+    (P.parse().unwrap(), G.parse().unwrap(), Q.parse().unwrap())
+}
+
+/// Bob, who takes any public key he's given at face value and sends back a
+/// message MAC'd under the resulting shared secret.
+fn make_oracle(
+    p: &BigUint,
+    g: &BigUint,
+    q: &BigUint,
+) -> (BigUint, impl Fn(&BigUint) -> (Vec<u8>, Vec<u8>)) {
+    let private = BigUint::random_range(&BigUint::one(), q);
+    let public = g.modpow(&private, p);
+    let p = p.clone();
+    (public, move |h: &BigUint| {
+        let secret = h.modpow(&private, &p);
+        let msg = dlog::BOB_MESSAGE.to_vec();
+        let mac = dlog::mac(&secret, &msg);
+        (msg, mac)
+    })
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(58, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        assert!(solve("").is_some());
+    }
+
+    #[test]
+    fn test_kangaroo() {
+        let (p, g, _) = params();
+        let (a, b) = (BigUint::zero(), BigUint::from(1u64 << 20));
+        let x = BigUint::random_range(&a, &b);
+        let y = g.modpow(&x, &p);
+        assert_eq!(dlog::kangaroo(&g, &y, &p, &a, &b), Some(x));
+    }
+}
@@ -0,0 +1,69 @@
+// ECDH and Invalid-Curve Attacks
+use cpr::{
+    bigint::BigUint,
+    dlog,
+    ec::{self, Group, Point},
+};
+use std::error::Error;
+
+/// Curves with the same a as the challenge curve, as (b, number of points).
+const WEAK_CURVES: [(u64, &str); 3] = [
+    (210, "233970423115425145550826547352470124412"),
+    (504, "233970423115425145544350131142039591210"),
+    (727, "233970423115425145545378039958152057148"),
+];
+
+pub fn solve(_input: &str) -> Option<String> {
+    let group = Group::challenge();
+    let (public, oracle) = make_oracle(&group);
+    let (x, _) = ec::invalid_curve(&group, &weak_curves(), oracle)?;
+    (group.curve.mul(&group.g, &x) == public).then(|| x.to_string())
+}
+
+fn weak_curves() -> Vec<(BigUint, BigUint)> {
+    WEAK_CURVES
+        .iter()
+        .map(|&(b, order)| (BigUint::from(b), order.parse().unwrap()))
+        .collect()
+}
+
+/// Bob, who takes any point he's given at face value (without checking that
+/// it's on the curve) and sends back a message MAC'd under the resulting
+/// shared secret.
+fn make_oracle(group: &Group) -> (Point, impl Fn(&Point) -> (Vec<u8>, Vec<u8>)) {
+    let keys = group.keypair();
+    let group = group.clone();
+    (keys.public.clone(), move |h: &Point| {
+        let secret = keys.shared_secret(&group, h);
+        let msg = dlog::BOB_MESSAGE.to_vec();
+        let mac = ec::mac(&secret, &msg);
+        (msg, mac)
+    })
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(59, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        assert!(solve("").is_some());
+    }
+
+    #[test]
+    fn test_weak_curves() {
+        let curve = Group::challenge().curve;
+        for (b, order) in weak_curves() {
+            let twin = curve.with_b(b);
+            let point = twin.random_point();
+            assert!(twin.contains(&point));
+            assert!(!curve.contains(&point));
+            assert_eq!(curve.mul(&point, &order), Point::Infinity);
+        }
+    }
+}
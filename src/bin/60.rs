@@ -0,0 +1,63 @@
+// Single-Coordinate Ladders and Insecure Twists
+use cpr::{
+    bigint::BigUint,
+    dlog,
+    montgomery::{self, Group},
+};
+use std::error::Error;
+
+/// Use every factor of the twist's order up to here, which leaves about 40
+/// bits for the kangaroo.
+const BOUND: u64 = 1 << 22;
+
+pub fn solve(_input: &str) -> Option<String> {
+    let group = Group::challenge();
+    let (public, oracle) = make_oracle(&group);
+    let x = montgomery::twist_attack(&group, &public, BOUND, oracle)?;
+    (group.curve.ladder(&group.u, &x) == public).then(|| x.to_string())
+}
+
+/// Bob, who takes any u-coordinate he's given without checking whether it's
+/// on the curve or on the twist, and sends back a message MAC'd under the
+/// resulting shared secret.
+fn make_oracle(group: &Group) -> (BigUint, impl Fn(&BigUint) -> (Vec<u8>, Vec<u8>)) {
+    let keys = group.keypair();
+    let group = group.clone();
+    (keys.public.clone(), move |u: &BigUint| {
+        let secret = keys.shared_secret(&group, u);
+        let msg = dlog::BOB_MESSAGE.to_vec();
+        let mac = dlog::mac(&secret, &msg);
+        (msg, mac)
+    })
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(60, solve, "").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn test() {
+        // Takes several minutes even in a release build, mostly kangaroo
+        assert!(solve("").is_some());
+    }
+
+    #[test]
+    fn test_small_key() {
+        // Same curve, but Bob picks a 40-bit key, so that the small factors
+        // of the twist's order leave only a few bits for the kangaroo
+        let group = Group {
+            n: BigUint::one() << 40,
+            ..Group::challenge()
+        };
+        let (public, oracle) = make_oracle(&group);
+        let x = montgomery::twist_attack(&group, &public, 1 << 12, oracle).unwrap();
+        assert!(x < group.n);
+        assert_eq!(group.curve.ladder(&group.u, &x), public);
+    }
+}
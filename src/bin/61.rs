@@ -0,0 +1,173 @@
+// Duplicate-Signature Key Selection in ECDSA (and RSA)
+use cpr::{
+    bigint::{self, BigUint},
+    dlog,
+    ec::{Group, Keypair, Point, Signature},
+    hash::sha256::Sha256,
+    rsa::{self, DigestInfo, PublicKey},
+};
+use rand::seq::SliceRandom;
+use std::error::Error;
+
+/// How many q's to try against each p.
+const Q_TRIES: usize = 16;
+
+pub fn solve(input: &str) -> Option<String> {
+    let msg = input.trim().as_bytes();
+
+    // Alice signs with ECDSA, and Eve comes up with a key of her own that the
+    // same signature checks out under
+    let group = Group::challenge();
+    let alice = group.keypair();
+    let sig = alice.sign(&group, msg);
+    let (eve_group, eve) = ecdsa_dsks(&group, &alice.public, msg, &sig)?;
+    if eve.public == alice.public || !eve_group.verify(&eve.public, msg, &sig) {
+        return None;
+    }
+
+    // Same again with RSA
+    let (alice, private) = rsa::keygen(1024, 65537);
+    let sig = private.sign::<Sha256>(msg)?;
+    let eve = rsa_dsks::<Sha256>(&alice, msg, &sig)?;
+    (eve != alice && eve.verify::<Sha256>(msg, &sig)).then(|| input.trim().into())
+}
+
+/// Make a keypair (on the same curve, but with a new generator) under which
+/// `sig` is also a good signature on `msg`. The verifier ends up comparing
+/// against R = u1 * G + u2 * Q, so pick any d' and then a G' for which
+/// u1 * G' + u2 * d' * G' = R too.
+fn ecdsa_dsks(
+    group: &Group,
+    public: &Point,
+    msg: &[u8],
+    sig: &Signature,
+) -> Option<(Group, Keypair)> {
+    let (curve, n) = (&group.curve, &group.n);
+    let w = sig.s.modinv(n)?;
+    let u1 = &(&group.hash(msg) * &w) % n;
+    let u2 = &(&sig.r * &w) % n;
+    let r = curve.add(&curve.mul(&group.g, &u1), &curve.mul(public, &u2));
+
+    let private = BigUint::random_range(&BigUint::one(), n);
+    let t = &(&u1 + &(&u2 * &private)) % n;
+    let g = curve.mul(&r, &t.modinv(n)?);
+    let public = curve.mul(&g, &private);
+    Some((
+        Group::new(curve.clone(), g, n.clone()),
+        Keypair { private, public },
+    ))
+}
+
+/// Make an RSA public key (the same size as the original) under which `sig`
+/// is also a good signature on `msg`. With p - 1 and q - 1 smooth, discrete
+/// logs base s are easy, so solve s^e' = pad(m) mod p and mod q and CRT the
+/// two together.
+fn rsa_dsks<H: DigestInfo>(public: &PublicKey, msg: &[u8], sig: &[u8]) -> Option<PublicKey> {
+    let s = BigUint::from_bytes_be(sig);
+    let pad = BigUint::from_bytes_be(&rsa::pkcs1_v15_sign_pad::<H>(msg, public.size())?);
+    let bits = public.n.bits();
+
+    // Split the small primes between p - 1 and q - 1, so that the only factor
+    // they have in common is 2
+    let primes = (3..1 << 12)
+        .filter(|&f| rsa::is_probable_prime(&BigUint::from(f), 1))
+        .collect::<Vec<u64>>();
+    let (for_p, for_q): (Vec<_>, Vec<_>) = primes.chunks_exact(2).map(|c| (c[0], c[1])).unzip();
+
+    loop {
+        // s has to generate the whole group, or pad(m) might be out of reach
+        let (p, factors) = smooth_prime(bits / 2, &for_p);
+        if !generates(&s, &p, &factors) {
+            continue;
+        }
+        let ep = dlog::pohlig_hellman(&s, &pad, &p, &factors)?;
+
+        // A p near the bottom of its range leaves no q that makes n' big
+        // enough, so only give each p a few goes before starting over
+        for _ in 0..Q_TRIES {
+            let (q, factors) = smooth_prime(bits - bits / 2, &for_q);
+            let n = &p * &q;
+            if n.bits() != bits || n <= s || !generates(&s, &q, &factors) {
+                continue;
+            }
+
+            // e' = ep mod p - 1 and eq mod q - 1, which can only both be true
+            // if they agree mod 2. If they do, then e' = eq mod (q - 1) / 2
+            // (which is odd, and shares nothing with p - 1) is enough.
+            let eq = dlog::pohlig_hellman(&s, &pad, &q, &factors)?;
+            if ep.is_even() != eq.is_even() {
+                continue;
+            }
+            let half = &(&q - 1) >> 1;
+            let e = bigint::crt(&[ep.clone(), &eq % &half], &[&p - 1, half])?;
+            return Some(PublicKey { n, e });
+        }
+    }
+}
+
+/// A random prime p of exactly `bits` bits, where p - 1 is 2 times a product
+/// of distinct primes from `pool`. Returns p along with all the factors of
+/// p - 1.
+fn smooth_prime(bits: usize, pool: &[u64]) -> (BigUint, Vec<u64>) {
+    let mut rng = rand::thread_rng();
+    loop {
+        let mut factors = vec![2];
+        let mut acc = BigUint::from(2u64);
+        while acc.bits() < bits {
+            let &f = pool.choose(&mut rng).unwrap();
+            if !factors.contains(&f) {
+                acc = &acc * f;
+                factors.push(f);
+            }
+        }
+        let p = &acc + 1;
+        if p.bits() == bits && rsa::is_probable_prime(&p, 20) {
+            return (p, factors);
+        }
+    }
+}
+
+/// Whether g generates all of (Z/pZ)*, given the prime factors of p - 1.
+fn generates(g: &BigUint, p: &BigUint, factors: &[u64]) -> bool {
+    let order = p - 1;
+    factors
+        .iter()
+        .all(|&f| !g.modpow(&(&order / f), p).is_one())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    cpr::solve!(61, solve, "hi mom").ok_or("no solution")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<(), Box<dyn Error>> {
+        let want = Some("hi mom".into());
+        let got = solve("hi mom");
+        assert_eq!(want, got);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rsa_dsks() {
+        let (public, private) = rsa::keygen(512, 65537);
+        for msg in [&b"hi mom"[..], b"hi dad"] {
+            let sig = private.sign::<Sha256>(msg).unwrap();
+            let eve = rsa_dsks::<Sha256>(&public, msg, &sig).unwrap();
+            assert_ne!(eve, public);
+            assert!(eve.verify::<Sha256>(msg, &sig));
+        }
+    }
+
+    #[test]
+    fn test_smooth_prime() {
+        let (p, factors) = smooth_prime(32, &[3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41]);
+        assert_eq!(p.bits(), 32);
+        let product = factors.iter().fold(BigUint::one(), |acc, &f| &acc * f);
+        assert_eq!(&product + 1, p);
+    }
+}
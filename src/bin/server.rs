@@ -0,0 +1,47 @@
+use cpr::{timing::Server, utils};
+use std::{error::Error, time::Duration};
+
+struct Args {
+    port: u16,
+    delay: f64,
+    key: Option<String>,
+}
+
+fn parse_args() -> Result<Args, pico_args::Error> {
+    let mut args = pico_args::Arguments::from_env();
+    Ok(Args {
+        port: args.opt_value_from_str("--port")?.unwrap_or(9000),
+        delay: args.opt_value_from_str("--delay")?.unwrap_or(50.),
+        key: args.opt_value_from_str("--key")?,
+    })
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!(
+                "{}. example: `cargo run --bin server -- --port 9000 --delay 5`",
+                e
+            );
+            std::process::exit(1);
+        }
+    };
+
+    let key = match args.key {
+        Some(key) => key.into_bytes(),
+        None => utils::rand_bytes(16),
+    };
+    let delay = Duration::from_secs_f64(args.delay / 1000.);
+    let server = Server::start(&key, delay, args.port)?;
+
+    eprintln!(
+        "{}Listening on http://{}/test?file=...&signature=... ({:?} per byte){}",
+        cpr::GREY,
+        server.addr(),
+        delay,
+        cpr::RESET
+    );
+    server.join();
+    Ok(())
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+const WINDOW_SIZE: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+/// How many earlier occurrences of a 3-byte prefix to consider for a match.
+const MAX_CHAIN: usize = 128;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Output of the LZ77 pass.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token {
+    Literal(u8),
+    /// Copy `length` bytes starting `distance` bytes back.
+    Match {
+        length: usize,
+        distance: usize,
+    },
+}
+
+/// Greedy LZ77: at each position, take the longest match in the window (the
+/// closest one if there's a tie), or emit a literal if there's nothing at
+/// least three bytes long.
+///
+/// # Examples
+/// ```
+/// use cpr::deflate::{lz77, Token};
+/// let tokens = lz77(b"abcabcabc");
+/// assert_eq!(
+///     tokens,
+///     vec![
+///         Token::Literal(b'a'),
+///         Token::Literal(b'b'),
+///         Token::Literal(b'c'),
+///         Token::Match { length: 6, distance: 3 },
+///     ]
+/// );
+/// ```
+pub fn lz77(bytes: &[u8]) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut chains: HashMap<&[u8], Vec<usize>> = HashMap::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let mut best = (0, 0);
+        if let Some(candidates) = bytes.get(i..i + MIN_MATCH).and_then(|k| chains.get(k)) {
+            let max_len = MAX_MATCH.min(bytes.len() - i);
+            for &j in candidates.iter().rev().take(MAX_CHAIN) {
+                if i - j > WINDOW_SIZE {
+                    break;
+                }
+                let len = (0..max_len)
+                    .take_while(|&k| bytes[j + k] == bytes[i + k])
+                    .count();
+                if len > best.0 {
+                    best = (len, i - j);
+                }
+            }
+        }
+
+        let advance = if best.0 >= MIN_MATCH {
+            tokens.push(Token::Match {
+                length: best.0,
+                distance: best.1,
+            });
+            best.0
+        } else {
+            tokens.push(Token::Literal(bytes[i]));
+            1
+        };
+        for k in i..i + advance {
+            if let Some(key) = bytes.get(k..k + MIN_MATCH) {
+                chains.entry(key).or_default().push(k);
+            }
+        }
+        i += advance;
+    }
+
+    tokens
+}
+
+/// Compress into a raw DEFLATE stream (RFC 1951): a single block, using the
+/// fixed Huffman codes.
+///
+/// See challenge 51.
+///
+/// # Examples
+/// ```
+/// use cpr::deflate;
+/// let msg = b"sessionid=TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=\nsessionid=T";
+/// let compressed = deflate::compress(msg);
+/// assert!(compressed.len() < msg.len());
+/// assert_eq!(deflate::decompress(&compressed), Some(msg.to_vec()));
+/// assert_eq!(deflate::decompress(&deflate::compress(b"")), Some(vec![]));
+/// ```
+pub fn compress(bytes: &[u8]) -> Vec<u8> {
+    let mut out = BitWriter::default();
+    out.write_bits(1, 1); // BFINAL
+    out.write_bits(1, 2); // BTYPE = fixed Huffman
+
+    for token in lz77(bytes) {
+        match token {
+            Token::Literal(b) => write_literal(&mut out, b as u16),
+            Token::Match { length, distance } => {
+                let code = LENGTH_BASE
+                    .iter()
+                    .rposition(|&b| b as usize <= length)
+                    .unwrap();
+                write_literal(&mut out, 257 + code as u16);
+                out.write_bits(length - LENGTH_BASE[code] as usize, LENGTH_EXTRA[code]);
+
+                let code = DIST_BASE
+                    .iter()
+                    .rposition(|&b| b as usize <= distance)
+                    .unwrap();
+                out.write_code(code as u16, 5);
+                out.write_bits(distance - DIST_BASE[code] as usize, DIST_EXTRA[code]);
+            }
+        }
+    }
+    write_literal(&mut out, 256);
+    out.finish()
+}
+
+/// Write a literal/length symbol with its fixed Huffman code.
+fn write_literal(out: &mut BitWriter, symbol: u16) {
+    match symbol {
+        0..=143 => out.write_code(0x30 + symbol, 8),
+        144..=255 => out.write_code(0x190 + symbol - 144, 9),
+        256..=279 => out.write_code(symbol - 256, 7),
+        _ => out.write_code(0xc0 + symbol - 280, 8),
+    }
+}
+
+/// Decompress a raw DEFLATE stream. Only stored and fixed Huffman blocks are
+/// supported, which covers anything that came out of [`compress`].
+pub fn decompress(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut input = BitReader::new(bytes);
+    let mut out: Vec<u8> = vec![];
+
+    loop {
+        let last = input.read_bits(1)? == 1;
+        match input.read_bits(2)? {
+            0 => {
+                input.align();
+                let len = input.read_bits(16)?;
+                let nlen = input.read_bits(16)?;
+                if len != !nlen & 0xffff {
+                    return None;
+                }
+                for _ in 0..len {
+                    out.push(input.read_bits(8)? as u8);
+                }
+            }
+            1 => loop {
+                let symbol = read_literal(&mut input)?;
+                match symbol {
+                    0..=255 => out.push(symbol as u8),
+                    256 => break,
+                    _ => {
+                        let code = (symbol - 257) as usize;
+                        let length = *LENGTH_BASE.get(code)? as usize
+                            + input.read_bits(LENGTH_EXTRA[code])?;
+                        let code = input.read_code(5)? as usize;
+                        let distance =
+                            *DIST_BASE.get(code)? as usize + input.read_bits(DIST_EXTRA[code])?;
+                        let start = out.len().checked_sub(distance)?;
+                        // The source and destination can overlap, so go one
+                        // byte at a time
+                        for k in 0..length {
+                            out.push(out[start + k]);
+                        }
+                    }
+                }
+            },
+            _ => return None,
+        }
+        if last {
+            return Some(out);
+        }
+    }
+}
+
+/// Read a literal/length symbol, one bit at a time, using the fixed Huffman
+/// code's ranges.
+fn read_literal(input: &mut BitReader) -> Option<u16> {
+    let code = input.read_code(7)?;
+    if code <= 0x17 {
+        return Some(code + 256);
+    }
+    let code = code << 1 | input.read_bits(1)? as u16;
+    match code {
+        0x30..=0xbf => Some(code - 0x30),
+        0xc0..=0xc7 => Some(code - 0xc0 + 280),
+        _ => {
+            let code = code << 1 | input.read_bits(1)? as u16;
+            Some(code - 0x190 + 144)
+        }
+    }
+}
+
+/// Packs bits least significant first, as DEFLATE wants.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    bit: u8,
+}
+
+impl BitWriter {
+    fn write_bits(&mut self, value: usize, count: u8) {
+        for k in 0..count {
+            if self.bit == 0 {
+                self.bytes.push(0);
+            }
+            if value >> k & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 1 << self.bit;
+            }
+            self.bit = (self.bit + 1) % 8;
+        }
+    }
+
+    /// Huffman codes go in most significant bit first.
+    fn write_code(&mut self, code: u16, len: u8) {
+        for k in (0..len).rev() {
+            self.write_bits((code >> k & 1) as usize, 1);
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn read_bits(&mut self, count: u8) -> Option<usize> {
+        let mut value = 0;
+        for k in 0..count {
+            let byte = self.bytes.get(self.pos / 8)?;
+            value |= ((byte >> (self.pos % 8) & 1) as usize) << k;
+            self.pos += 1;
+        }
+        Some(value)
+    }
+
+    fn read_code(&mut self, len: u8) -> Option<u16> {
+        let mut code = 0;
+        for _ in 0..len {
+            code = code << 1 | self.read_bits(1)? as u16;
+        }
+        Some(code)
+    }
+
+    fn align(&mut self) {
+        self.pos = (self.pos + 7) / 8 * 8;
+    }
+}
@@ -0,0 +1,251 @@
+use crate::{
+    bigint::BigUint,
+    hash::sha1,
+    utils::{self, Op},
+};
+use std::{
+    error::Error,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+/// The 1536-bit MODP group from RFC 3526, as used by the challenges.
+pub const NIST_P: &str = "\
+    ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74\
+    020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f1437\
+    4fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7ed\
+    ee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf05\
+    98da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb\
+    9ed529077096966d670c354e4abc9804f1746c08ca237327ffffffffffffffff";
+
+pub const NIST_G: u64 = 2;
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Diffie-Hellman group parameters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Group {
+    pub p: BigUint,
+    pub g: BigUint,
+}
+
+impl Group {
+    pub fn new(p: BigUint, g: BigUint) -> Self {
+        Self { p, g }
+    }
+
+    pub fn nist() -> Self {
+        Self::new(BigUint::from_hex(NIST_P).unwrap(), BigUint::from(NIST_G))
+    }
+
+    pub fn keypair(&self) -> Keypair {
+        let private = BigUint::random_below(&self.p);
+        let public = self.g.modpow(&private, &self.p);
+        Keypair { private, public }
+    }
+}
+
+/// A Diffie-Hellman keypair.
+///
+/// See challenge 33.
+///
+/// # Examples
+/// ```
+/// use cpr::dh::Group;
+/// let group = Group::nist();
+/// let alice = group.keypair();
+/// let bob = group.keypair();
+/// assert_eq!(
+///     alice.shared_secret(&group, &bob.public),
+///     bob.shared_secret(&group, &alice.public),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Keypair {
+    pub private: BigUint,
+    pub public: BigUint,
+}
+
+impl Keypair {
+    pub fn shared_secret(&self, group: &Group, other: &BigUint) -> BigUint {
+        other.modpow(&self.private, &group.p)
+    }
+}
+
+/// Turn a shared secret into an AES-128 key: the first 16 bytes of its SHA-1.
+pub fn derive_key(secret: &BigUint) -> Vec<u8> {
+    sha1::digest(&secret.to_bytes_be())[..16].to_vec()
+}
+
+/// Encrypt with AES-CBC under a random IV. Always pads, even a message that's
+/// already block aligned, so that `decrypt` can tell a wrong key apart.
+pub fn encrypt(msg: &[u8], key: &[u8]) -> Message {
+    let iv = utils::rand_bytes(16);
+    let pad = 16 - msg.len() % 16;
+    let padded = [msg, &vec![pad as u8; pad]].concat();
+    let ct = utils::cbc(&padded, key, &iv, Op::Encrypt);
+    Message::Data { ct, iv }
+}
+
+/// Decrypt and strip the padding. `None` if it isn't a data message, if the
+/// ciphertext isn't a whole number of blocks, or if the padding is bad (which
+/// almost always means the key was wrong).
+///
+/// # Examples
+/// ```
+/// use cpr::{bigint::BigUint, dh, utils::{self, Op}};
+/// let key = dh::derive_key(&BigUint::from(1337u64));
+/// let msg = dh::encrypt(b"YELLOW SUBMARINE", &key);
+/// assert_eq!(dh::decrypt(&msg, &key), Some(b"YELLOW SUBMARINE".to_vec()));
+///
+/// let iv = vec![0; 16];
+/// let ct = utils::cbc(b"YELLOW SUBMARINE!", &key, &iv, Op::Encrypt);
+/// let wrong = dh::derive_key(&BigUint::from(1338u64));
+/// assert_eq!(dh::decrypt(&dh::Message::Data { ct, iv: iv.clone() }, &wrong), None);
+///
+/// let ct = vec![];
+/// assert_eq!(dh::decrypt(&dh::Message::Data { ct, iv }, &key), None);
+/// ```
+pub fn decrypt(msg: &Message, key: &[u8]) -> Option<Vec<u8>> {
+    match msg {
+        Message::Data { ct, iv } if !ct.is_empty() && ct.len() % 16 == 0 => {
+            let pt = utils::cbc(ct, key, iv, Op::Decrypt);
+            if utils::pkcs7_valid(&pt, 16) {
+                utils::pkcs7_unpad(&pt)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Everything that goes over the wire.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Message {
+    /// Proposed (or, coming back, accepted) group parameters.
+    Negotiate(Group),
+    PublicKey(BigUint),
+    Data {
+        ct: Vec<u8>,
+        iv: Vec<u8>,
+    },
+}
+
+/// Which way a message is headed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    AliceToBob,
+    BobToAlice,
+}
+
+/// One end of a bidirectional channel.
+pub struct Endpoint {
+    tx: Sender<Message>,
+    rx: Receiver<Message>,
+}
+
+impl Endpoint {
+    pub fn send(&self, msg: Message) -> Result<()> {
+        Ok(self.tx.send(msg)?)
+    }
+
+    pub fn recv(&self) -> Result<Message> {
+        Ok(self.rx.recv()?)
+    }
+}
+
+/// Create a connected pair of endpoints.
+pub fn pipe() -> (Endpoint, Endpoint) {
+    let (tx1, rx1) = mpsc::channel();
+    let (tx2, rx2) = mpsc::channel();
+    (Endpoint { tx: tx1, rx: rx2 }, Endpoint { tx: tx2, rx: rx1 })
+}
+
+/// Alice proposes a group, agrees on a key, sends `msg` and returns whatever
+/// Bob echoes back.
+pub fn alice(group: Group, msg: &[u8], wire: Endpoint) -> Result<Vec<u8>> {
+    wire.send(Message::Negotiate(group))?;
+    let group = match wire.recv()? {
+        Message::Negotiate(group) => group,
+        other => return Err(format!("expected group, got {:?}", other).into()),
+    };
+
+    let keys = group.keypair();
+    wire.send(Message::PublicKey(keys.public.clone()))?;
+    let key = match wire.recv()? {
+        Message::PublicKey(public) => derive_key(&keys.shared_secret(&group, &public)),
+        other => return Err(format!("expected public key, got {:?}", other).into()),
+    };
+
+    wire.send(encrypt(msg, &key))?;
+    decrypt(&wire.recv()?, &key).ok_or_else(|| "expected data".into())
+}
+
+/// Bob accepts whatever group he's offered, agrees on a key, then decrypts a
+/// message and echoes it back re-encrypted under a fresh IV.
+pub fn bob(wire: Endpoint) -> Result<Vec<u8>> {
+    let group = match wire.recv()? {
+        Message::Negotiate(group) => group,
+        other => return Err(format!("expected group, got {:?}", other).into()),
+    };
+    wire.send(Message::Negotiate(group.clone()))?;
+
+    let keys = group.keypair();
+    let key = match wire.recv()? {
+        Message::PublicKey(public) => derive_key(&keys.shared_secret(&group, &public)),
+        other => return Err(format!("expected public key, got {:?}", other).into()),
+    };
+    wire.send(Message::PublicKey(keys.public.clone()))?;
+
+    let msg = decrypt(&wire.recv()?, &key).ok_or("expected data")?;
+    wire.send(encrypt(&msg, &key))?;
+    Ok(msg)
+}
+
+/// Run Alice and Bob on their own threads with `middle` sitting on the wire
+/// between them, free to read and rewrite every message. Returns what Alice
+/// got echoed back and what Bob received.
+///
+/// See challenges 34 and 35.
+///
+/// # Examples
+/// ```
+/// use cpr::dh::{self, Group, Message};
+/// let mut seen = vec![];
+/// let (alice, bob) = dh::exchange(Group::nist(), b"hello", |_, msg: Message| {
+///     seen.push(msg.clone());
+///     msg
+/// })
+/// .unwrap();
+/// assert_eq!(alice, b"hello");
+/// assert_eq!(bob, b"hello");
+/// assert_eq!(seen.len(), 6);
+/// ```
+pub fn exchange(
+    group: Group,
+    msg: &[u8],
+    mut middle: impl FnMut(Direction, Message) -> Message,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let (alice_end, alice_wire) = pipe();
+    let (bob_end, bob_wire) = pipe();
+
+    let msg = msg.to_vec();
+    let alice = thread::spawn(move || alice(group, &msg, alice_end));
+    let bob = thread::spawn(move || bob(bob_end));
+
+    // The protocol is strictly request/response, so just ping-pong until
+    // somebody hangs up.
+    while let Ok(msg) = alice_wire.recv() {
+        bob_wire.send(middle(Direction::AliceToBob, msg))?;
+        match bob_wire.recv() {
+            Ok(msg) => alice_wire.send(middle(Direction::BobToAlice, msg))?,
+            Err(_) => break,
+        }
+    }
+    drop((alice_wire, bob_wire));
+
+    let alice = alice.join().map_err(|_| "alice panicked")??;
+    let bob = bob.join().map_err(|_| "bob panicked")??;
+    Ok((alice, bob))
+}
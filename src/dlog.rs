@@ -0,0 +1,227 @@
+use crate::{
+    bigint::{self, BigUint},
+    hash::sha256::Sha256,
+    mac::Hmac,
+};
+
+/// The message Bob MACs whenever somebody hands him a public key.
+pub const BOB_MESSAGE: &[u8] = b"crazy flamboyant for the rap enjoyment";
+
+/// What Bob sends back: a message and its HMAC-SHA256 keyed with the shared
+/// secret.
+pub fn mac(secret: &BigUint, msg: &[u8]) -> Vec<u8> {
+    Hmac::<Sha256>::new(&secret.to_bytes_be()).sign(msg)
+}
+
+/// The distinct prime factors of `n` that are less than `bound`, by trial
+/// division.
+///
+/// # Examples
+/// ```
+/// use cpr::{bigint::BigUint, dlog::small_factors};
+/// let n = BigUint::from(2u64 * 2 * 3 * 101 * 65537);
+/// assert_eq!(small_factors(&n, 1 << 16), vec![2, 3, 101]);
+/// ```
+pub fn small_factors(n: &BigUint, bound: u64) -> Vec<u64> {
+    let mut n = n.clone();
+    let mut factors = vec![];
+    for f in 2..bound {
+        if (&n % f).is_zero() {
+            factors.push(f);
+            while (&n % f).is_zero() {
+                n = &n / f;
+            }
+        }
+    }
+    factors
+}
+
+/// A random element of order `r` in the multiplicative group mod `p`, where
+/// `r` is a prime dividing `p - 1`.
+pub fn element_of_order(p: &BigUint, r: u64) -> BigUint {
+    let exp = &(p - 1) / r;
+    loop {
+        let h = BigUint::random_range(&BigUint::from(2u64), p).modpow(&exp, p);
+        if !h.is_one() {
+            return h;
+        }
+    }
+}
+
+/// Pohlig-Hellman by subgroup confinement: hand Bob elements of small prime
+/// order r (for each factor of `j` = (p - 1) / q below 2^16), brute force his
+/// private key mod r from the MAC he sends back, and CRT it all together.
+/// Stops as soon as the combined modulus passes `enough`, and returns the key
+/// as (residue, modulus).
+///
+/// See challenge 57.
+pub fn subgroup_confinement(
+    p: &BigUint,
+    j: &BigUint,
+    enough: &BigUint,
+    oracle: impl Fn(&BigUint) -> (Vec<u8>, Vec<u8>),
+) -> Option<(BigUint, BigUint)> {
+    let (mut residues, mut moduli) = (vec![], vec![]);
+    let mut modulus = BigUint::one();
+    for r in small_factors(j, 1 << 16) {
+        if &modulus > enough {
+            break;
+        }
+
+        let h = element_of_order(p, r);
+        let (msg, t) = oracle(&h);
+        let mut k = BigUint::one();
+        let b = (0..r).find(|_| {
+            let found = mac(&k, &msg) == t;
+            k = &(&k * &h) % p;
+            found
+        })?;
+
+        residues.push(BigUint::from(b));
+        moduli.push(BigUint::from(r));
+        modulus = &modulus * r;
+    }
+    Some((bigint::crt(&residues, &moduli)?, modulus))
+}
+
+/// Pohlig-Hellman: find the x in [0, p - 1) with h = g^x mod p, where g
+/// generates the whole group and p - 1 is the product of the distinct small
+/// primes in `factors`. Each x mod r comes from a brute force in the subgroup
+/// of order r, and then it's all CRT'd together.
+///
+/// p - 1 has to be squarefree: a repeated factor would need x mod r^2 (and so
+/// on), which this doesn't work out, so it returns `None` rather than a wrong
+/// answer.
+///
+/// See challenge 61.
+///
+/// # Examples
+/// ```
+/// use cpr::{bigint::BigUint, dlog::pohlig_hellman};
+/// // 2311 - 1 = 2 * 3 * 5 * 7 * 11, and 3 is a generator
+/// let (p, g) = (BigUint::from(2311u64), BigUint::from(3u64));
+/// let h = g.modpow(&BigUint::from(1234u64), &p);
+/// assert_eq!(pohlig_hellman(&g, &h, &p, &[2, 3, 5, 7, 11]), Some(BigUint::from(1234u64)));
+///
+/// // 13 - 1 = 2^2 * 3 isn't squarefree, and 2^8 = 9 mod 13
+/// let (p, g, h) = (BigUint::from(13u64), BigUint::from(2u64), BigUint::from(9u64));
+/// assert_eq!(pohlig_hellman(&g, &h, &p, &[2, 3]), None);
+/// ```
+pub fn pohlig_hellman(g: &BigUint, h: &BigUint, p: &BigUint, factors: &[u64]) -> Option<BigUint> {
+    let order = p - 1;
+    let (mut residues, mut moduli) = (vec![], vec![]);
+    for &r in factors {
+        let exp = &order / r;
+        let (g_r, h_r) = (g.modpow(&exp, p), h.modpow(&exp, p));
+        let mut k = BigUint::one();
+        let b = (0..r).find(|_| {
+            let found = k == h_r;
+            k = &(&k * &g_r) % p;
+            found
+        })?;
+        residues.push(BigUint::from(b));
+        moduli.push(BigUint::from(r));
+    }
+    let x = bigint::crt(&residues, &moduli)?;
+    (g.modpow(&x, p) == h % p).then_some(x)
+}
+
+/// Just enough of a cyclic group to go hunting kangaroos in.
+pub trait Group {
+    type Element: Clone + PartialEq;
+
+    fn op(&self, x: &Self::Element, y: &Self::Element) -> Self::Element;
+
+    fn pow(&self, x: &Self::Element, k: &BigUint) -> Self::Element;
+
+    /// Some cheap, deterministic function of an element, for picking jumps.
+    fn index(&self, x: &Self::Element) -> u64;
+}
+
+/// The multiplicative group mod p.
+struct Multiplicative<'a>(&'a BigUint);
+
+impl Group for Multiplicative<'_> {
+    type Element = BigUint;
+
+    fn op(&self, x: &BigUint, y: &BigUint) -> BigUint {
+        &(x * y) % self.0
+    }
+
+    fn pow(&self, x: &BigUint, k: &BigUint) -> BigUint {
+        x.modpow(k, self.0)
+    }
+
+    fn index(&self, x: &BigUint) -> u64 {
+        (x & 0xffff_ffff).to_u64().unwrap()
+    }
+}
+
+/// Pollard's kangaroo (lambda) algorithm: find the x in [a, b] with
+/// y = g^x mod p, taking about sqrt(b - a) steps.
+///
+/// See challenge 58.
+///
+/// # Examples
+/// ```
+/// use cpr::{bigint::BigUint, dlog::kangaroo};
+/// let p = BigUint::from(1_000_000_007u64);
+/// let g = BigUint::from(5u64);
+/// let x = BigUint::from(123_456u64);
+/// let y = g.modpow(&x, &p);
+/// let (a, b) = (BigUint::from(100_000u64), BigUint::from(200_000u64));
+/// assert_eq!(kangaroo(&g, &y, &p, &a, &b), Some(x));
+/// ```
+pub fn kangaroo(
+    g: &BigUint,
+    y: &BigUint,
+    p: &BigUint,
+    a: &BigUint,
+    b: &BigUint,
+) -> Option<BigUint> {
+    kangaroo_in(&Multiplicative(p), g, y, a, b)
+}
+
+/// Pollard's kangaroo in any group: find the x in [a, b] with y = g^x.
+///
+/// See challenge 60.
+pub fn kangaroo_in<G: Group>(
+    group: &G,
+    g: &G::Element,
+    y: &G::Element,
+    a: &BigUint,
+    b: &BigUint,
+) -> Option<BigUint> {
+    let width = (b - a).to_u64()?;
+
+    // Jumps are powers of two below 2^k, picked so that the average jump is
+    // about half the square root of the interval; the tame kangaroo makes
+    // four times that many of them.
+    let half_root = ((width as f64).sqrt() / 2.0).max(1.0);
+    let k = (1..63u32)
+        .find(|&k| ((1u64 << k) - 1) as f64 / k as f64 >= half_root)
+        .unwrap_or(63);
+    let jumps = (0..k)
+        .map(|i| group.pow(g, &BigUint::from(1u64 << i)))
+        .collect::<Vec<_>>();
+    let f = |y: &G::Element| (group.index(y) % k as u64) as usize;
+    let n = 4 * ((1u64 << k) - 1) / k as u64;
+
+    // The tame kangaroo starts at b and leaves a trap where it stops
+    let mut tame = (0u64, group.pow(g, b));
+    for _ in 0..n {
+        let i = f(&tame.1);
+        tame = (tame.0 + (1 << i), group.op(&tame.1, &jumps[i]));
+    }
+
+    // The wild one starts at y and either lands in the trap or runs past it
+    let mut wild = (0u64, y.clone());
+    while wild.0 <= width + tame.0 {
+        if wild.1 == tame.1 {
+            return Some(&(b + tame.0) - wild.0);
+        }
+        let i = f(&wild.1);
+        wild = (wild.0 + (1 << i), group.op(&wild.1, &jumps[i]));
+    }
+    None
+}
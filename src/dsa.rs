@@ -0,0 +1,156 @@
+use crate::{bigint::BigUint, hash::sha1};
+
+/// The DSA domain parameters used by the challenges.
+pub const CHALLENGE_P: &str = "\
+    800000000000000089e1855218a0e7dac38136ffafa72eda7859f2171e25e65e\
+    ac698c1702578b07dc2a1076da241c76c62d374d8389ea5aeffd3226a0530cc5\
+    65f3bf6b50929139ebeac04f48c3c84afb796d61e5a4f9a8fda812ab59494232\
+    c7d2b4deb50aa18ee9e132bfa85ac4374d7f9091abc3d015efc871a584471bb1";
+
+pub const CHALLENGE_Q: &str = "f4f47f05794b256174bba6e9b396a7707e563c5b";
+
+pub const CHALLENGE_G: &str = "\
+    5958c9d3898b224b12672c0b98e06c60df923cb8bc999d119458fef538b8fa40\
+    46c8db53039db620c094c9fa077ef389b5322a559946a71903f990f1f7e0e025\
+    e2d7f7cf494aff1a0470f5b64c36b625a097f1651fe775323556fe00b3608c88\
+    7892878480e99041be601a62166ca6894bdd41a7054ec89f756ba9fc95302291";
+
+/// DSA domain parameters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Params {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+}
+
+impl Params {
+    pub fn new(p: BigUint, q: BigUint, g: BigUint) -> Self {
+        Self { p, q, g }
+    }
+
+    pub fn challenge() -> Self {
+        Self::new(
+            BigUint::from_hex(CHALLENGE_P).unwrap(),
+            BigUint::from_hex(CHALLENGE_Q).unwrap(),
+            BigUint::from_hex(CHALLENGE_G).unwrap(),
+        )
+    }
+
+    pub fn keypair(&self) -> Keypair {
+        let private = BigUint::random_range(&BigUint::one(), &self.q);
+        Keypair {
+            public: self.public_key(&private),
+            private,
+        }
+    }
+
+    /// y = g^x mod p
+    pub fn public_key(&self, private: &BigUint) -> BigUint {
+        self.g.modpow(private, &self.p)
+    }
+
+    /// Verify a signature the way the standard says to, rejecting r and s
+    /// outside of (0, q).
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::dsa::Params;
+    /// let params = Params::challenge();
+    /// let keys = params.keypair();
+    /// let sig = keys.sign(&params, b"hi mom");
+    /// assert!(params.verify(&keys.public, b"hi mom", &sig));
+    /// assert!(!params.verify(&keys.public, b"hi dad", &sig));
+    /// ```
+    pub fn verify(&self, public: &BigUint, msg: &[u8], sig: &Signature) -> bool {
+        let in_range = |v: &BigUint| !v.is_zero() && v < &self.q;
+        in_range(&sig.r) && in_range(&sig.s) && self.verify_unchecked(public, msg, sig)
+    }
+
+    /// Verify a signature without checking that r and s are in range.
+    ///
+    /// See challenge 45.
+    pub fn verify_unchecked(&self, public: &BigUint, msg: &[u8], sig: &Signature) -> bool {
+        let w = match sig.s.modinv(&self.q) {
+            Some(w) => w,
+            None => return false,
+        };
+        let u1 = &(&hash(msg) * &w) % &self.q;
+        let u2 = &(&sig.r * &w) % &self.q;
+        let v = &(&self.g.modpow(&u1, &self.p) * &public.modpow(&u2, &self.p)) % &self.p;
+        &v % &self.q == sig.r
+    }
+
+    /// Sign with a caller-chosen nonce, without checking that the result is
+    /// any good.
+    pub fn sign_with_k(&self, private: &BigUint, msg: &[u8], k: &BigUint) -> Signature {
+        let r = &self.g.modpow(k, &self.p) % &self.q;
+        let k_inv = k.modinv(&self.q).unwrap_or_else(BigUint::zero);
+        let s = &(&k_inv * &(&hash(msg) + &(private * &r))) % &self.q;
+        Signature { r, s }
+    }
+
+    /// Recover the private key from a signature, given the nonce that was used
+    /// to make it: x = (s * k - H(m)) / r mod q.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::{bigint::BigUint, dsa::Params};
+    /// let params = Params::challenge();
+    /// let keys = params.keypair();
+    /// let k = BigUint::from(1337u64);
+    /// let sig = params.sign_with_k(&keys.private, b"hi mom", &k);
+    /// let h = cpr::dsa::hash(b"hi mom");
+    /// assert_eq!(params.private_from_nonce(&h, &sig, &k), Some(keys.private));
+    /// ```
+    pub fn private_from_nonce(&self, h: &BigUint, sig: &Signature, k: &BigUint) -> Option<BigUint> {
+        let q = &self.q;
+        let sk = &(&sig.s * k) % q;
+        let numerator = &(&(&sk + q) - &(h % q)) % q;
+        Some(&(&numerator * &sig.r.modinv(q)?) % q)
+    }
+}
+
+/// A DSA keypair.
+///
+/// See challenge 43.
+#[derive(Clone, Debug)]
+pub struct Keypair {
+    pub private: BigUint,
+    pub public: BigUint,
+}
+
+impl Keypair {
+    pub fn sign(&self, params: &Params, msg: &[u8]) -> Signature {
+        loop {
+            let k = BigUint::random_range(&BigUint::one(), &params.q);
+            let sig = params.sign_with_k(&self.private, msg, &k);
+            if !sig.r.is_zero() && !sig.s.is_zero() {
+                return sig;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+/// The SHA-1 of a message, as an integer.
+pub fn hash(msg: &[u8]) -> BigUint {
+    BigUint::from_bytes_be(&sha1::digest(msg))
+}
+
+/// The SHA-1 of the hex encoding of a private key, which is how the challenges
+/// check your answer without giving it away.
+///
+/// # Examples
+/// ```
+/// use cpr::{bigint::BigUint, dsa};
+/// let x = BigUint::from(0xdeadbeefu64);
+/// assert_eq!(dsa::fingerprint(&x), hex::encode(cpr::hash::sha1::digest(b"deadbeef")));
+/// ```
+pub fn fingerprint(private: &BigUint) -> String {
+    hex::encode(sha1::digest(private.to_hex().as_bytes()))
+}
@@ -0,0 +1,525 @@
+use crate::{
+    bigint::{self, BigUint},
+    dlog, dsa,
+    hash::sha256::Sha256,
+    mac::Hmac,
+};
+
+/// The curve used by the challenges: y^2 = x^3 - 95051x + 11279326 over
+/// GF(p), with a base point of order n.
+pub const CHALLENGE_P: &str = "233970423115425145524320034830162017933";
+pub const CHALLENGE_A: i64 = -95051;
+pub const CHALLENGE_B: u64 = 11279326;
+pub const CHALLENGE_GX: u64 = 182;
+pub const CHALLENGE_GY: &str = "85518893674295321206118380980485522083";
+pub const CHALLENGE_N: &str = "29246302889428143187362802287225875743";
+
+/// A short Weierstrass curve y^2 = x^3 + ax + b over GF(p).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Curve {
+    pub p: BigUint,
+    pub a: BigUint,
+    pub b: BigUint,
+}
+
+/// A point in affine coordinates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Point {
+    Infinity,
+    Affine(BigUint, BigUint),
+}
+
+impl Point {
+    /// The coordinates as bytes, or nothing at all for the point at infinity.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Point::Infinity => vec![],
+            Point::Affine(x, y) => [x.to_bytes_be(), y.to_bytes_be()].concat(),
+        }
+    }
+}
+
+/// A point in Jacobian coordinates, (X, Y, Z) standing for (X/Z^2, Y/Z^3).
+/// Anything with Z = 0 is the point at infinity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Jacobian {
+    pub x: BigUint,
+    pub y: BigUint,
+    pub z: BigUint,
+}
+
+impl Curve {
+    pub fn new(p: BigUint, a: BigUint, b: BigUint) -> Self {
+        Self { p, a, b }
+    }
+
+    pub fn challenge() -> Self {
+        let p: BigUint = CHALLENGE_P.parse().unwrap();
+        let a = &p - CHALLENGE_A.unsigned_abs();
+        Self::new(p, a, BigUint::from(CHALLENGE_B))
+    }
+
+    /// The same curve, but with a different b. The addition formulas never
+    /// look at b, so points on one can be fed to code expecting the other.
+    pub fn with_b(&self, b: BigUint) -> Self {
+        Self::new(self.p.clone(), self.a.clone(), b)
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        match point {
+            Point::Infinity => true,
+            Point::Affine(x, y) => self.mul_mod(y, y) == self.rhs(x),
+        }
+    }
+
+    /// x^3 + ax + b
+    fn rhs(&self, x: &BigUint) -> BigUint {
+        let x3 = self.mul_mod(&self.mul_mod(x, x), x);
+        &(&(&x3 + &self.mul_mod(&self.a, x)) + &self.b) % &self.p
+    }
+
+    pub fn neg(&self, point: &Point) -> Point {
+        match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine(x, y) => Point::Affine(x.clone(), self.sub_mod(&BigUint::zero(), y)),
+        }
+    }
+
+    /// Add two points in affine coordinates, which costs an inversion.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::ec::{Curve, Group, Point};
+    /// let group = Group::challenge();
+    /// let curve = &group.curve;
+    /// let g2 = curve.add(&group.g, &group.g);
+    /// assert!(curve.contains(&g2));
+    /// assert_eq!(curve.add(&g2, &curve.neg(&group.g)), group.g);
+    /// assert_eq!(curve.add(&group.g, &curve.neg(&group.g)), Point::Infinity);
+    /// assert_eq!(curve.add(&Point::Infinity, &group.g), group.g);
+    /// ```
+    pub fn add(&self, p1: &Point, p2: &Point) -> Point {
+        let (x1, y1, x2, y2) = match (p1, p2) {
+            (Point::Infinity, _) => return p2.clone(),
+            (_, Point::Infinity) => return p1.clone(),
+            (Point::Affine(x1, y1), Point::Affine(x2, y2)) => (x1, y1, x2, y2),
+        };
+        if p1 == &self.neg(p2) {
+            return Point::Infinity;
+        }
+
+        // Neither denominator can be zero now, since that would mean p1 = -p2
+        let slope = if p1 == p2 {
+            let num = &(&BigUint::from(3u64) * &self.mul_mod(x1, x1)) + &self.a;
+            self.mul_mod(&num, &self.inv(&(y1 * 2)).unwrap())
+        } else {
+            self.mul_mod(
+                &self.sub_mod(y2, y1),
+                &self.inv(&self.sub_mod(x2, x1)).unwrap(),
+            )
+        };
+        let x3 = self.sub_mod(&self.sub_mod(&self.mul_mod(&slope, &slope), x1), x2);
+        let y3 = self.sub_mod(&self.mul_mod(&slope, &self.sub_mod(x1, &x3)), y1);
+        Point::Affine(x3, y3)
+    }
+
+    pub fn to_jacobian(&self, point: &Point) -> Jacobian {
+        match point {
+            Point::Infinity => Jacobian {
+                x: BigUint::one(),
+                y: BigUint::one(),
+                z: BigUint::zero(),
+            },
+            Point::Affine(x, y) => Jacobian {
+                x: x.clone(),
+                y: y.clone(),
+                z: BigUint::one(),
+            },
+        }
+    }
+
+    pub fn to_affine(&self, point: &Jacobian) -> Point {
+        let z_inv = match self.inv(&point.z) {
+            Some(z_inv) => z_inv,
+            None => return Point::Infinity,
+        };
+        let z_inv2 = self.mul_mod(&z_inv, &z_inv);
+        Point::Affine(
+            self.mul_mod(&point.x, &z_inv2),
+            self.mul_mod(&point.y, &self.mul_mod(&z_inv2, &z_inv)),
+        )
+    }
+
+    /// Double a point in Jacobian coordinates, without any inversions.
+    pub fn double_jacobian(&self, p: &Jacobian) -> Jacobian {
+        if p.z.is_zero() || p.y.is_zero() {
+            return self.to_jacobian(&Point::Infinity);
+        }
+        let y2 = self.mul_mod(&p.y, &p.y);
+        let s = self.mul_mod(&(&p.x * 4), &y2);
+        let z2 = self.mul_mod(&p.z, &p.z);
+        let m = &(&self.mul_mod(&p.x, &p.x) * 3) + &self.mul_mod(&self.a, &self.mul_mod(&z2, &z2));
+        let x = self.sub_mod(&self.mul_mod(&m, &m), &(&s * 2));
+        let y = self.sub_mod(
+            &self.mul_mod(&m, &self.sub_mod(&s, &x)),
+            &self.mul_mod(&y2, &(&y2 * 8)),
+        );
+        let z = self.mul_mod(&(&p.y * 2), &p.z);
+        Jacobian { x, y, z }
+    }
+
+    /// Add two points in Jacobian coordinates, without any inversions.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::ec::Group;
+    /// let group = Group::challenge();
+    /// let curve = &group.curve;
+    /// let g = curve.to_jacobian(&group.g);
+    /// let g3 = curve.add_jacobian(&curve.double_jacobian(&g), &g);
+    /// let want = curve.add(&curve.add(&group.g, &group.g), &group.g);
+    /// assert_eq!(curve.to_affine(&g3), want);
+    /// ```
+    pub fn add_jacobian(&self, p1: &Jacobian, p2: &Jacobian) -> Jacobian {
+        if p1.z.is_zero() {
+            return p2.clone();
+        }
+        if p2.z.is_zero() {
+            return p1.clone();
+        }
+        let z1z1 = self.mul_mod(&p1.z, &p1.z);
+        let z2z2 = self.mul_mod(&p2.z, &p2.z);
+        let u1 = self.mul_mod(&p1.x, &z2z2);
+        let u2 = self.mul_mod(&p2.x, &z1z1);
+        let s1 = self.mul_mod(&p1.y, &self.mul_mod(&z2z2, &p2.z));
+        let s2 = self.mul_mod(&p2.y, &self.mul_mod(&z1z1, &p1.z));
+        if u1 == u2 {
+            return if s1 == s2 {
+                self.double_jacobian(p1)
+            } else {
+                self.to_jacobian(&Point::Infinity)
+            };
+        }
+
+        let h = self.sub_mod(&u2, &u1);
+        let r = self.sub_mod(&s2, &s1);
+        let h2 = self.mul_mod(&h, &h);
+        let h3 = self.mul_mod(&h2, &h);
+        let u1h2 = self.mul_mod(&u1, &h2);
+        let x = self.sub_mod(&self.sub_mod(&self.mul_mod(&r, &r), &h3), &(&u1h2 * 2));
+        let y = self.sub_mod(
+            &self.mul_mod(&r, &self.sub_mod(&u1h2, &x)),
+            &self.mul_mod(&s1, &h3),
+        );
+        let z = self.mul_mod(&h, &self.mul_mod(&p1.z, &p2.z));
+        Jacobian { x, y, z }
+    }
+
+    /// Scalar multiplication: double-and-add in Jacobian coordinates.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::{bigint::BigUint, ec::{Group, Point}};
+    /// let group = Group::challenge();
+    /// let curve = &group.curve;
+    /// assert_eq!(curve.mul(&group.g, &group.n), Point::Infinity);
+    /// assert_eq!(curve.mul(&group.g, &(&group.n + 1)), group.g);
+    /// let g5 = (0..5).fold(Point::Infinity, |acc, _| curve.add(&acc, &group.g));
+    /// assert_eq!(curve.mul(&group.g, &BigUint::from(5u64)), g5);
+    /// ```
+    pub fn mul(&self, point: &Point, k: &BigUint) -> Point {
+        let base = self.to_jacobian(point);
+        let mut out = self.to_jacobian(&Point::Infinity);
+        for i in (0..k.bits()).rev() {
+            out = self.double_jacobian(&out);
+            if k.bit(i) {
+                out = self.add_jacobian(&out, &base);
+            }
+        }
+        self.to_affine(&out)
+    }
+
+    /// One of the (up to two) points with a given x-coordinate, if there are
+    /// any.
+    pub fn lift_x(&self, x: &BigUint) -> Option<Point> {
+        let y = sqrt_mod(&self.rhs(x), &self.p)?;
+        Some(Point::Affine(x % &self.p, y))
+    }
+
+    /// A random point on the curve (other than the point at infinity).
+    pub fn random_point(&self) -> Point {
+        loop {
+            if let Some(point) = self.lift_x(&BigUint::random_below(&self.p)) {
+                return point;
+            }
+        }
+    }
+
+    /// A random point of prime order `r`, on a curve with `order` points.
+    pub fn point_of_order(&self, order: &BigUint, r: u64) -> Point {
+        // If r^2 divides the order, order / r can kill every point, so strip
+        // out all the factors of r and then multiply back up by r until the
+        // next step would land on the point at infinity
+        let mut cofactor = order.clone();
+        while (&cofactor % r).is_zero() {
+            cofactor = &cofactor / r;
+        }
+        let r_big = BigUint::from(r);
+        loop {
+            let mut point = self.mul(&self.random_point(), &cofactor);
+            if point == Point::Infinity {
+                continue;
+            }
+            loop {
+                let next = self.mul(&point, &r_big);
+                if next == Point::Infinity {
+                    return point;
+                }
+                point = next;
+            }
+        }
+    }
+
+    fn mul_mod(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        &(a * b) % &self.p
+    }
+
+    fn sub_mod(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        &(&(&(a % &self.p) + &self.p) - &(b % &self.p)) % &self.p
+    }
+
+    fn inv(&self, a: &BigUint) -> Option<BigUint> {
+        (a % &self.p).modinv(&self.p)
+    }
+}
+
+impl dlog::Group for Curve {
+    type Element = Point;
+
+    fn op(&self, x: &Point, y: &Point) -> Point {
+        self.add(x, y)
+    }
+
+    fn pow(&self, x: &Point, k: &BigUint) -> Point {
+        self.mul(x, k)
+    }
+
+    fn index(&self, x: &Point) -> u64 {
+        match x {
+            Point::Infinity => 0,
+            Point::Affine(x, _) => (x & 0xffff_ffff).to_u64().unwrap(),
+        }
+    }
+}
+
+/// A curve along with a base point and its order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Group {
+    pub curve: Curve,
+    pub g: Point,
+    pub n: BigUint,
+}
+
+impl Group {
+    pub fn new(curve: Curve, g: Point, n: BigUint) -> Self {
+        Self { curve, g, n }
+    }
+
+    pub fn challenge() -> Self {
+        let g = Point::Affine(BigUint::from(CHALLENGE_GX), CHALLENGE_GY.parse().unwrap());
+        Self::new(Curve::challenge(), g, CHALLENGE_N.parse().unwrap())
+    }
+
+    pub fn keypair(&self) -> Keypair {
+        let private = BigUint::random_range(&BigUint::one(), &self.n);
+        let public = self.curve.mul(&self.g, &private);
+        Keypair { private, public }
+    }
+
+    /// Verify an ECDSA signature, rejecting r and s outside of (0, n).
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::ec::Group;
+    /// let group = Group::challenge();
+    /// let keys = group.keypair();
+    /// let sig = keys.sign(&group, b"hi mom");
+    /// assert!(group.verify(&keys.public, b"hi mom", &sig));
+    /// assert!(!group.verify(&keys.public, b"hi dad", &sig));
+    /// assert!(!group.verify(&group.keypair().public, b"hi mom", &sig));
+    /// ```
+    pub fn verify(&self, public: &Point, msg: &[u8], sig: &Signature) -> bool {
+        let in_range = |v: &BigUint| !v.is_zero() && v < &self.n;
+        if !in_range(&sig.r) || !in_range(&sig.s) {
+            return false;
+        }
+        let w = match sig.s.modinv(&self.n) {
+            Some(w) => w,
+            None => return false,
+        };
+        let u1 = &(&self.hash(msg) * &w) % &self.n;
+        let u2 = &(&sig.r * &w) % &self.n;
+        match self
+            .curve
+            .add(&self.curve.mul(&self.g, &u1), &self.curve.mul(public, &u2))
+        {
+            Point::Affine(x, _) => &x % &self.n == sig.r,
+            Point::Infinity => false,
+        }
+    }
+
+    /// The SHA-1 of a message as an integer, cut down to as many bits as n.
+    pub fn hash(&self, msg: &[u8]) -> BigUint {
+        dsa::hash(msg) >> 160usize.saturating_sub(self.n.bits())
+    }
+}
+
+/// An elliptic curve keypair, for ECDH or ECDSA.
+///
+/// See challenges 59 and 61.
+///
+/// # Examples
+/// ```
+/// use cpr::ec::Group;
+/// let group = Group::challenge();
+/// let alice = group.keypair();
+/// let bob = group.keypair();
+/// assert!(group.curve.contains(&alice.public));
+/// assert_eq!(
+///     alice.shared_secret(&group, &bob.public),
+///     bob.shared_secret(&group, &alice.public),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Keypair {
+    pub private: BigUint,
+    pub public: Point,
+}
+
+impl Keypair {
+    /// Note that this doesn't check that `other` is actually on the curve.
+    pub fn shared_secret(&self, group: &Group, other: &Point) -> Point {
+        group.curve.mul(other, &self.private)
+    }
+
+    /// ECDSA signature over the (truncated) SHA-1 of `msg`.
+    pub fn sign(&self, group: &Group, msg: &[u8]) -> Signature {
+        loop {
+            let k = BigUint::random_range(&BigUint::one(), &group.n);
+            let (r, k_inv) = match (group.curve.mul(&group.g, &k), k.modinv(&group.n)) {
+                (Point::Affine(x, _), Some(k_inv)) => (&x % &group.n, k_inv),
+                _ => continue,
+            };
+            let e = &group.hash(msg) + &(&r * &self.private);
+            let s = &(&k_inv * &e) % &group.n;
+            if !r.is_zero() && !s.is_zero() {
+                return Signature { r, s };
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+/// HMAC-SHA256 keyed with a shared secret point.
+pub fn mac(secret: &Point, msg: &[u8]) -> Vec<u8> {
+    Hmac::<Sha256>::new(&secret.to_bytes()).sign(msg)
+}
+
+/// Square root mod a prime (Tonelli-Shanks), if there is one.
+///
+/// # Examples
+/// ```
+/// use cpr::{bigint::BigUint, ec::sqrt_mod};
+/// let p: BigUint = cpr::ec::CHALLENGE_P.parse().unwrap();
+/// let n = BigUint::from(1337u64);
+/// let r = sqrt_mod(&(&n * &n), &p).unwrap();
+/// assert!(r == n || r == &p - &n);
+/// assert_eq!(sqrt_mod(&BigUint::from(2u64), &BigUint::from(13u64)), None);
+/// ```
+pub fn sqrt_mod(n: &BigUint, p: &BigUint) -> Option<BigUint> {
+    let n = n % p;
+    if n.is_zero() {
+        return Some(n);
+    }
+
+    // Euler's criterion
+    let p1 = p - 1;
+    let half = &p1 >> 1;
+    if !n.modpow(&half, p).is_one() {
+        return None;
+    }
+
+    // p - 1 = q * 2^s, and z is any non-residue
+    let s = p1.trailing_zeros();
+    let q = &p1 >> s;
+    let z = (2u64..)
+        .map(BigUint::from)
+        .find(|z| z.modpow(&half, p) == p1)?;
+
+    let mut m = s;
+    let mut c = z.modpow(&q, p);
+    let mut t = n.modpow(&q, p);
+    let mut r = n.modpow(&(&(&q + 1) >> 1), p);
+    while !t.is_one() {
+        let mut i = 0;
+        let mut t2i = t.clone();
+        while !t2i.is_one() {
+            t2i = &(&t2i * &t2i) % p;
+            i += 1;
+        }
+        let mut b = c.clone();
+        for _ in 0..m - i - 1 {
+            b = &(&b * &b) % p;
+        }
+        m = i;
+        c = &(&b * &b) % p;
+        t = &(&t * &c) % p;
+        r = &(&r * &b) % p;
+    }
+    Some(r)
+}
+
+/// The invalid curve attack: hand Bob points of small prime order r that lie
+/// on other curves with the same a (and so the same addition formulas), brute
+/// force his private key mod r from the MAC he sends back, and CRT it all
+/// together. `curves` are the alternate b values along with how many points
+/// are on each curve. Returns the key as (residue, modulus).
+///
+/// See challenge 59.
+pub fn invalid_curve(
+    group: &Group,
+    curves: &[(BigUint, BigUint)],
+    oracle: impl Fn(&Point) -> (Vec<u8>, Vec<u8>),
+) -> Option<(BigUint, BigUint)> {
+    let curve = &group.curve;
+    let (mut residues, mut moduli) = (vec![], vec![]);
+    let mut modulus = BigUint::one();
+    for (b, order) in curves {
+        let twin = curve.with_b(b.clone());
+        for r in dlog::small_factors(order, 1 << 16) {
+            let r_big = BigUint::from(r);
+            if modulus > group.n || moduli.contains(&r_big) {
+                continue;
+            }
+
+            let h = twin.point_of_order(order, r);
+            let (msg, t) = oracle(&h);
+            let mut k = Point::Infinity;
+            let b = (0..r).find(|_| {
+                let found = mac(&k, &msg) == t;
+                k = curve.add(&k, &h);
+                found
+            })?;
+
+            residues.push(BigUint::from(b));
+            moduli.push(r_big);
+            modulus = &modulus * r;
+        }
+    }
+    Some((bigint::crt(&residues, &moduli)?, modulus))
+}
@@ -0,0 +1,58 @@
+pub mod md4;
+pub mod sha1;
+pub mod sha256;
+
+/// A hash function that can be plugged into generic constructions like HMAC.
+///
+/// # Examples
+/// ```
+/// use cpr::hash::{sha1::Sha1, Hash};
+/// let msg = b"YELLOW SUBMARINE";
+/// assert_eq!(Sha1::digest(msg), <::sha1::Sha1 as Hash>::digest(msg));
+/// ```
+pub trait Hash {
+    /// Size of the input block in bytes.
+    const BLOCK_SIZE: usize;
+
+    /// Size of the digest in bytes.
+    const DIGEST_SIZE: usize;
+
+    fn digest(bytes: &[u8]) -> Vec<u8>;
+}
+
+impl Hash for md4::Md4 {
+    const BLOCK_SIZE: usize = md4::BLOCK_SIZE;
+    const DIGEST_SIZE: usize = md4::DIGEST_SIZE;
+
+    fn digest(bytes: &[u8]) -> Vec<u8> {
+        md4::digest(bytes).to_vec()
+    }
+}
+
+impl Hash for sha1::Sha1 {
+    const BLOCK_SIZE: usize = sha1::BLOCK_SIZE;
+    const DIGEST_SIZE: usize = sha1::DIGEST_SIZE;
+
+    fn digest(bytes: &[u8]) -> Vec<u8> {
+        sha1::digest(bytes).to_vec()
+    }
+}
+
+impl Hash for sha256::Sha256 {
+    const BLOCK_SIZE: usize = sha256::BLOCK_SIZE;
+    const DIGEST_SIZE: usize = sha256::DIGEST_SIZE;
+
+    fn digest(bytes: &[u8]) -> Vec<u8> {
+        sha256::digest(bytes).to_vec()
+    }
+}
+
+// The opaque implementation from the sha1 crate, used back in challenge 28.
+impl Hash for ::sha1::Sha1 {
+    const BLOCK_SIZE: usize = 64;
+    const DIGEST_SIZE: usize = 20;
+
+    fn digest(bytes: &[u8]) -> Vec<u8> {
+        <::sha1::Sha1 as ::sha1::Digest>::digest(bytes).to_vec()
+    }
+}
@@ -0,0 +1,221 @@
+/// Size of an MD4 block in bytes.
+pub const BLOCK_SIZE: usize = 64;
+
+/// Size of an MD4 digest in bytes.
+pub const DIGEST_SIZE: usize = 16;
+
+/// Initial chaining registers.
+pub const INIT: [u32; 4] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476];
+
+/// MD4 with an exposed internal state.
+///
+/// See challenge 30 or https://www.rfc-editor.org/rfc/rfc1320
+///
+/// # Examples
+/// ```
+/// use cpr::hash::md4::Md4;
+/// let mut md4 = Md4::new();
+/// md4.update(b"message ");
+/// md4.update(b"digest");
+/// assert_eq!(
+///     hex::encode(md4.finalize()),
+///     "d9130a8164549fe818874806e1c7014b"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Md4 {
+    state: [u32; 4],
+    len: u64,
+    buf: Vec<u8>,
+}
+
+impl Default for Md4 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Md4 {
+    pub fn new() -> Self {
+        Self::from_state(INIT, 0)
+    }
+
+    /// Restart a hash from a set of chaining registers and the number of bytes
+    /// (including padding) that have already been processed.
+    pub fn from_state(state: [u32; 4], len: u64) -> Self {
+        Self {
+            state,
+            len,
+            buf: Vec::with_capacity(BLOCK_SIZE),
+        }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.len += bytes.len() as u64;
+        self.buf.extend_from_slice(bytes);
+        let full = self.buf.len() - self.buf.len() % BLOCK_SIZE;
+        for block in self.buf[..full].chunks(BLOCK_SIZE) {
+            compress(&mut self.state, block);
+        }
+        self.buf.drain(..full);
+    }
+
+    pub fn finalize(mut self) -> [u8; DIGEST_SIZE] {
+        let padding = md_padding(self.len);
+        self.update(&padding);
+        to_digest(&self.state)
+    }
+
+    /// Current chaining registers (only meaningful on a block boundary).
+    pub fn state(&self) -> [u32; 4] {
+        self.state
+    }
+}
+
+/// Hash some bytes in one go.
+///
+/// # Examples
+/// ```
+/// use cpr::hash::md4::digest;
+/// // Test suite from RFC 1320, appendix A.5
+/// let vectors: [(&[u8], &str); 7] = [
+///     (b"", "31d6cfe0d16ae931b73c59d7e0c089c0"),
+///     (b"a", "bde52cb31de33e46245e05fbdbd6fb24"),
+///     (b"abc", "a448017aaf21d8525fc10ae87aa6729d"),
+///     (b"message digest", "d9130a8164549fe818874806e1c7014b"),
+///     (b"abcdefghijklmnopqrstuvwxyz", "d79e1c308aa5bbcdeea8ed63df412da9"),
+///     (
+///         b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+///         "043f8582f241db351ce627e153e7f0e4",
+///     ),
+///     (
+///         b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+///         "e33b4ddc9c38f2199c3e7b164fcc0536",
+///     ),
+/// ];
+/// for (msg, want) in vectors {
+///     assert_eq!(hex::encode(digest(msg)), want);
+/// }
+/// ```
+pub fn digest(bytes: &[u8]) -> [u8; DIGEST_SIZE] {
+    let mut md4 = Md4::new();
+    md4.update(bytes);
+    md4.finalize()
+}
+
+/// Merkle-Damgård padding for a message of `len` bytes. Same as SHA-1, except
+/// that the bit length is encoded as a little-endian `u64`.
+///
+/// # Examples
+/// ```
+/// use cpr::hash::md4::md_padding;
+/// assert_eq!(md_padding(0).len(), 64);
+/// assert_eq!(md_padding(3)[53..], [24, 0, 0, 0, 0, 0, 0, 0]);
+/// ```
+pub fn md_padding(len: u64) -> Vec<u8> {
+    let zeroes = (BLOCK_SIZE * 2 - 9 - (len as usize % BLOCK_SIZE)) % BLOCK_SIZE;
+    let mut padding = vec![0x80];
+    padding.extend(vec![0; zeroes]);
+    padding.extend((len.wrapping_mul(8)).to_le_bytes());
+    padding
+}
+
+/// Recover the chaining registers from a digest.
+///
+/// # Examples
+/// ```
+/// use cpr::hash::md4::{from_digest, to_digest, INIT};
+/// assert_eq!(from_digest(&to_digest(&INIT)), INIT);
+/// ```
+pub fn from_digest(digest: &[u8]) -> [u32; 4] {
+    let mut state = [0; 4];
+    for (word, chunk) in state.iter_mut().zip(digest.chunks(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    state
+}
+
+/// Serialize the chaining registers into a digest.
+pub fn to_digest(state: &[u32; 4]) -> [u8; DIGEST_SIZE] {
+    let mut digest = [0; DIGEST_SIZE];
+    for (chunk, word) in digest.chunks_mut(4).zip(state.iter()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    digest
+}
+
+/// Split a 64-byte block into its sixteen little-endian message words.
+pub fn to_words(block: &[u8]) -> [u32; 16] {
+    let mut words = [0; 16];
+    for (word, chunk) in words.iter_mut().zip(block.chunks(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    words
+}
+
+/// Round 1 boolean function.
+pub fn f(x: u32, y: u32, z: u32) -> u32 {
+    (x & y) | (!x & z)
+}
+
+/// Round 2 boolean function.
+pub fn g(x: u32, y: u32, z: u32) -> u32 {
+    (x & y) | (x & z) | (y & z)
+}
+
+/// Round 3 boolean function.
+pub fn h(x: u32, y: u32, z: u32) -> u32 {
+    x ^ y ^ z
+}
+
+/// Round 2 additive constant.
+pub const K2: u32 = 0x5A827999;
+
+/// Round 3 additive constant.
+pub const K3: u32 = 0x6ED9EBA1;
+
+/// Order in which round 3 visits the message words.
+pub const ROUND3_ORDER: [usize; 16] = [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15];
+
+/// Run the compression function over a single 64-byte block.
+pub fn compress(state: &mut [u32; 4], block: &[u8]) {
+    let x = to_words(block);
+    let [mut a, mut b, mut c, mut d] = *state;
+
+    for i in (0..16).step_by(4) {
+        a = (a.wrapping_add(f(b, c, d)).wrapping_add(x[i])).rotate_left(3);
+        d = (d.wrapping_add(f(a, b, c)).wrapping_add(x[i + 1])).rotate_left(7);
+        c = (c.wrapping_add(f(d, a, b)).wrapping_add(x[i + 2])).rotate_left(11);
+        b = (b.wrapping_add(f(c, d, a)).wrapping_add(x[i + 3])).rotate_left(19);
+    }
+
+    for i in 0..4 {
+        let round = |w: u32, p: u32, q: u32, r: u32, k: usize, s: u32| {
+            (w.wrapping_add(g(p, q, r))
+                .wrapping_add(x[k])
+                .wrapping_add(K2))
+            .rotate_left(s)
+        };
+        a = round(a, b, c, d, i, 3);
+        d = round(d, a, b, c, i + 4, 5);
+        c = round(c, d, a, b, i + 8, 9);
+        b = round(b, c, d, a, i + 12, 13);
+    }
+
+    for i in (0..16).step_by(4) {
+        let round = |w: u32, p: u32, q: u32, r: u32, k: usize, s: u32| {
+            (w.wrapping_add(h(p, q, r))
+                .wrapping_add(x[k])
+                .wrapping_add(K3))
+            .rotate_left(s)
+        };
+        a = round(a, b, c, d, ROUND3_ORDER[i], 3);
+        d = round(d, a, b, c, ROUND3_ORDER[i + 1], 9);
+        c = round(c, d, a, b, ROUND3_ORDER[i + 2], 11);
+        b = round(b, c, d, a, ROUND3_ORDER[i + 3], 15);
+    }
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d]) {
+        *s = s.wrapping_add(v);
+    }
+}
@@ -0,0 +1,167 @@
+/// Size of a SHA-1 block in bytes.
+pub const BLOCK_SIZE: usize = 64;
+
+/// Size of a SHA-1 digest in bytes.
+pub const DIGEST_SIZE: usize = 20;
+
+/// Initial chaining registers.
+pub const INIT: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+/// SHA-1 with an exposed internal state.
+///
+/// See challenge 28 or https://en.wikipedia.org/wiki/SHA-1
+///
+/// # Examples
+/// ```
+/// use cpr::hash::sha1::Sha1;
+/// let mut sha = Sha1::new();
+/// sha.update(b"The quick brown fox ");
+/// sha.update(b"jumps over the lazy dog");
+/// assert_eq!(
+///     hex::encode(sha.finalize()),
+///     "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sha1 {
+    state: [u32; 5],
+    len: u64,
+    buf: Vec<u8>,
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha1 {
+    pub fn new() -> Self {
+        Self::from_state(INIT, 0)
+    }
+
+    /// Restart a hash from a set of chaining registers and the number of bytes
+    /// (including padding) that have already been processed.
+    pub fn from_state(state: [u32; 5], len: u64) -> Self {
+        Self {
+            state,
+            len,
+            buf: Vec::with_capacity(BLOCK_SIZE),
+        }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.len += bytes.len() as u64;
+        self.buf.extend_from_slice(bytes);
+        let full = self.buf.len() - self.buf.len() % BLOCK_SIZE;
+        for block in self.buf[..full].chunks(BLOCK_SIZE) {
+            compress(&mut self.state, block);
+        }
+        self.buf.drain(..full);
+    }
+
+    pub fn finalize(mut self) -> [u8; DIGEST_SIZE] {
+        let padding = md_padding(self.len);
+        self.update(&padding);
+        to_digest(&self.state)
+    }
+
+    /// Current chaining registers (only meaningful on a block boundary).
+    pub fn state(&self) -> [u32; 5] {
+        self.state
+    }
+}
+
+/// Hash some bytes in one go.
+///
+/// # Examples
+/// ```
+/// use ::sha1::{Digest, Sha1};
+/// use cpr::hash::sha1;
+/// let msg = b"YELLOW SUBMARINE".repeat(10);
+/// assert_eq!(sha1::digest(&msg)[..], Sha1::digest(&msg)[..]);
+/// ```
+pub fn digest(bytes: &[u8]) -> [u8; DIGEST_SIZE] {
+    let mut sha = Sha1::new();
+    sha.update(bytes);
+    sha.finalize()
+}
+
+/// Merkle-Damgård padding for a message of `len` bytes: a single 0x80 byte,
+/// zeroes up to 56 mod 64, then the bit length as a big-endian `u64`.
+///
+/// # Examples
+/// ```
+/// use cpr::hash::sha1::md_padding;
+/// assert_eq!(md_padding(0).len(), 64);
+/// assert_eq!(md_padding(55).len(), 9);
+/// assert_eq!(md_padding(56).len(), 72);
+/// assert_eq!(md_padding(3)[53..], [0, 0, 0, 0, 0, 0, 0, 24]);
+/// ```
+pub fn md_padding(len: u64) -> Vec<u8> {
+    let zeroes = (BLOCK_SIZE * 2 - 9 - (len as usize % BLOCK_SIZE)) % BLOCK_SIZE;
+    let mut padding = vec![0x80];
+    padding.extend(vec![0; zeroes]);
+    padding.extend((len.wrapping_mul(8)).to_be_bytes());
+    padding
+}
+
+/// Recover the chaining registers from a digest.
+///
+/// # Examples
+/// ```
+/// use cpr::hash::sha1::{from_digest, to_digest, INIT};
+/// assert_eq!(from_digest(&to_digest(&INIT)), INIT);
+/// ```
+pub fn from_digest(digest: &[u8]) -> [u32; 5] {
+    let mut state = [0; 5];
+    for (word, chunk) in state.iter_mut().zip(digest.chunks(4)) {
+        *word = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+    state
+}
+
+/// Serialize the chaining registers into a digest.
+pub fn to_digest(state: &[u32; 5]) -> [u8; DIGEST_SIZE] {
+    let mut digest = [0; DIGEST_SIZE];
+    for (chunk, word) in digest.chunks_mut(4).zip(state.iter()) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+/// Run the compression function over a single 64-byte block.
+pub fn compress(state: &mut [u32; 5], block: &[u8]) {
+    let mut w = [0u32; 80];
+    for (i, chunk) in block.chunks(4).enumerate() {
+        w[i] = u32::from_be_bytes(chunk.try_into().unwrap());
+    }
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+    for (i, wi) in w.iter().enumerate() {
+        let (f, k) = match i {
+            0..=19 => ((b & c) | (!b & d), 0x5A827999),
+            20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+            40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+            _ => (b ^ c ^ d, 0xCA62C1D6),
+        };
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(*wi);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e]) {
+        *s = s.wrapping_add(v);
+    }
+}
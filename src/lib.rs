@@ -1,5 +1,19 @@
 use std::{error::Error, fs};
 
+pub mod bigint;
+pub mod deflate;
+pub mod dh;
+pub mod dlog;
+pub mod dsa;
+pub mod ec;
+pub mod hash;
+pub mod mac;
+pub mod md;
+pub mod montgomery;
+pub mod rc4;
+pub mod rsa;
+pub mod srp;
+pub mod timing;
 pub mod utils;
 
 pub const GREY: &str = "\x1b[1;30m";
@@ -27,6 +41,7 @@ macro_rules! solve {
     }};
 }
 
+#[allow(clippy::collapsible_str_replace)]
 pub fn read_data(challenge: u8, reflow: bool) -> Result<String, Box<dyn Error>> {
     let cwd = std::env::current_dir()?;
     let filepath = cwd.join("data").join(format!("{:02}.txt", challenge));
@@ -0,0 +1,192 @@
+use crate::{
+    hash::Hash,
+    utils::{self, Op},
+};
+use std::{marker::PhantomData, thread, time::Duration};
+
+/// HMAC over any hash implementing [`Hash`].
+///
+/// See challenge 31 or https://www.rfc-editor.org/rfc/rfc2104
+///
+/// # Examples
+/// ```
+/// use cpr::{hash::sha1::Sha1, mac::Hmac};
+/// // Test cases from RFC 2202, section 3
+/// let long_key = [0xaa; 80];
+/// let vectors: [(&[u8], &[u8], &str); 7] = [
+///     (&[0x0b; 20], b"Hi There", "b617318655057264e28bc0b6fb378c8ef146be00"),
+///     (b"Jefe", b"what do ya want for nothing?", "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79"),
+///     (&[0xaa; 20], &[0xdd; 50], "125d7342b9ac11cd91a39af48aa17b4f63f175d3"),
+///     (
+///         &(0x01..=0x19).collect::<Vec<u8>>(),
+///         &[0xcd; 50],
+///         "4c9007f4026250c6bc8414f9bf50c86c2d7235da",
+///     ),
+///     (&[0x0c; 20], b"Test With Truncation", "4c1a03424b55e07fe7f27be1d58bb9324a9a5a04"),
+///     (
+///         &long_key,
+///         b"Test Using Larger Than Block-Size Key - Hash Key First",
+///         "aa4ae5e15272d00e95705637ce8a3b55ed402112",
+///     ),
+///     (
+///         &long_key,
+///         b"Test Using Larger Than Block-Size Key and Larger Than One Block-Size Data",
+///         "e8e99d0f45237d786d6bbaa7965c7808bbff1a91",
+///     ),
+/// ];
+/// for (key, msg, want) in vectors {
+///     let hmac = Hmac::<Sha1>::new(key);
+///     assert_eq!(hex::encode(hmac.sign(msg)), want);
+///     assert!(hmac.verify(msg, &hex::decode(want).unwrap()));
+/// }
+/// ```
+///
+/// ```
+/// use cpr::{hash::sha256::Sha256, mac::Hmac};
+/// // Test cases from RFC 4231, section 4
+/// let long_key = [0xaa; 131];
+/// let vectors: [(&[u8], &[u8], &str); 7] = [
+///     (
+///         &[0x0b; 20],
+///         b"Hi There",
+///         "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+///     ),
+///     (
+///         b"Jefe",
+///         b"what do ya want for nothing?",
+///         "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+///     ),
+///     (
+///         &[0xaa; 20],
+///         &[0xdd; 50],
+///         "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+///     ),
+///     (
+///         &(0x01..=0x19).collect::<Vec<u8>>(),
+///         &[0xcd; 50],
+///         "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+///     ),
+///     // Truncated to 128 bits
+///     (&[0x0c; 20], b"Test With Truncation", "a3b6167473100ee06e0c796c2955552b"),
+///     (
+///         &long_key,
+///         b"Test Using Larger Than Block-Size Key - Hash Key First",
+///         "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+///     ),
+///     (
+///         &long_key,
+///         b"This is a test using a larger than block-size key and a larger than \
+///           block-size data. The key needs to be hashed before being used by the \
+///           HMAC algorithm.",
+///         "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+///     ),
+/// ];
+/// for (key, msg, want) in vectors {
+///     let hmac = Hmac::<Sha256>::new(key);
+///     assert!(hex::encode(hmac.sign(msg)).starts_with(want));
+/// }
+/// ```
+pub struct Hmac<H: Hash> {
+    ipad: Vec<u8>,
+    opad: Vec<u8>,
+    hash: PhantomData<H>,
+}
+
+impl<H: Hash> Hmac<H> {
+    pub fn new(key: &[u8]) -> Self {
+        // Keys longer than a block get hashed, and then everything gets zero
+        // padded up to the block size.
+        let mut key = if key.len() > H::BLOCK_SIZE {
+            H::digest(key)
+        } else {
+            key.to_vec()
+        };
+        key.resize(H::BLOCK_SIZE, 0);
+
+        Self {
+            ipad: key.iter().map(|b| b ^ 0x36).collect(),
+            opad: key.iter().map(|b| b ^ 0x5c).collect(),
+            hash: PhantomData,
+        }
+    }
+
+    pub fn sign(&self, msg: &[u8]) -> Vec<u8> {
+        let inner = H::digest(&[&self.ipad, msg].concat());
+        H::digest(&[&self.opad[..], &inner].concat())
+    }
+
+    /// Check a MAC in constant time.
+    pub fn verify(&self, msg: &[u8], mac: &[u8]) -> bool {
+        constant_time_eq(&self.sign(msg), mac)
+    }
+}
+
+/// CBC-MAC with AES-128: the last block of the CBC encryption of `msg`. An
+/// empty message is padded out to a full block first.
+///
+/// See challenge 49.
+///
+/// # Examples
+/// ```
+/// use cpr::{mac::cbc_mac, utils::{self, Op}};
+/// let (key, iv) = (b"YELLOW SUBMARINE", [0; 16]);
+/// let msg = b"alert('MZA who was that?');\n";
+/// assert_eq!(hex::encode(cbc_mac(msg, key, &iv)), "296b8d7cb78a243dda4d0a61d33bbdd1");
+/// let ct = utils::cbc(msg, key, &iv, Op::Encrypt);
+/// assert_eq!(cbc_mac(msg, key, &iv), &ct[ct.len() - 16..]);
+/// assert_eq!(cbc_mac(b"", key, &iv), cbc_mac(&[16; 16], key, &iv));
+/// ```
+pub fn cbc_mac(msg: &[u8], key: &[u8], iv: &[u8]) -> Vec<u8> {
+    // utils::cbc leaves block aligned input alone, which for an empty message
+    // would leave nothing to take the last block of
+    let msg = if msg.is_empty() { &[16; 16][..] } else { msg };
+    let ct = utils::cbc(msg, key, iv, Op::Encrypt);
+    ct[ct.len() - 16..].to_vec()
+}
+
+/// Compare two byte sequences without leaking where they first differ.
+///
+/// # Examples
+/// ```
+/// use cpr::mac::constant_time_eq;
+/// assert!(constant_time_eq(b"foo", b"foo"));
+/// assert!(!constant_time_eq(b"foo", b"bar"));
+/// assert!(!constant_time_eq(b"foo", b"foobar"));
+/// ```
+pub fn constant_time_eq(b1: &[u8], b2: &[u8]) -> bool {
+    if b1.len() != b2.len() {
+        return false;
+    }
+    b1.iter()
+        .zip(b2.iter())
+        .fold(0, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+/// Compare two byte sequences one byte at a time, sleeping after each byte and
+/// bailing out on the first mismatch. Don't use this for anything but timing
+/// attacks.
+///
+/// See challenge 31.
+///
+/// # Examples
+/// ```
+/// use cpr::mac::insecure_compare;
+/// use std::time::Duration;
+/// assert!(insecure_compare(b"foo", b"foo", Duration::ZERO));
+/// assert!(!insecure_compare(b"foo", b"fob", Duration::ZERO));
+/// ```
+pub fn insecure_compare(b1: &[u8], b2: &[u8], delay: Duration) -> bool {
+    if b1.len() != b2.len() {
+        return false;
+    }
+    for (x, y) in b1.iter().zip(b2.iter()) {
+        if x != y {
+            return false;
+        }
+        if !delay.is_zero() {
+            thread::sleep(delay);
+        }
+    }
+    true
+}
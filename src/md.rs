@@ -0,0 +1,415 @@
+use aes::{
+    cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit},
+    Aes128,
+};
+use std::{
+    collections::HashMap,
+    ops::RangeInclusive,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+pub const BLOCK_SIZE: usize = 16;
+
+type Compress = dyn Fn(&[u8], &[u8]) -> Vec<u8> + Send + Sync;
+
+/// A Merkle-Damgård hash built from an arbitrary compression function, which
+/// keeps count of how many times it's been called.
+///
+/// See challenge 52.
+///
+/// # Examples
+/// ```
+/// use cpr::md::MerkleDamgard;
+/// let md = MerkleDamgard::toy(2);
+/// let digest = md.digest(b"YELLOW SUBMARINE");
+/// assert_eq!(digest.len(), 2);
+/// // One block of message and one of padding
+/// assert_eq!(md.calls(), 2);
+/// assert_eq!(md.digest(b"YELLOW SUBMARINE"), digest);
+/// ```
+pub struct MerkleDamgard {
+    iv: Vec<u8>,
+    compress: Box<Compress>,
+    calls: AtomicUsize,
+}
+
+impl MerkleDamgard {
+    /// `compress` takes the current state and a 16-byte block, and returns the
+    /// next state, which must be the same size as `iv`.
+    pub fn new(
+        iv: &[u8],
+        compress: impl Fn(&[u8], &[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            iv: iv.to_vec(),
+            compress: Box::new(compress),
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// A toy hash with a `state_size`-byte state: encrypt each message block
+    /// with AES-128 keyed by the state, and keep the first `state_size` bytes
+    /// of the result. The key is padded out with copies of `state_size`, so
+    /// that toy hashes of different sizes don't share any keys.
+    pub fn toy(state_size: usize) -> Self {
+        assert!(state_size <= 16, "state doesn't fit in an AES key");
+        let iv = (0..state_size as u8).collect::<Vec<_>>();
+        Self::new(&iv, move |state, block| {
+            let mut key = state.to_vec();
+            key.resize(16, state_size as u8);
+            let cipher = Aes128::new(GenericArray::from_slice(&key));
+            let mut block = GenericArray::clone_from_slice(block);
+            cipher.encrypt_block(&mut block);
+            block[..state_size].to_vec()
+        })
+    }
+
+    pub fn iv(&self) -> &[u8] {
+        &self.iv
+    }
+
+    pub fn state_size(&self) -> usize {
+        self.iv.len()
+    }
+
+    /// Run the compression function once.
+    pub fn compress(&self, state: &[u8], block: &[u8]) -> Vec<u8> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        (self.compress)(state, block)
+    }
+
+    /// Run the compression function over every block of `blocks`, which must
+    /// already be a whole number of blocks long.
+    pub fn iterate(&self, state: &[u8], blocks: &[u8]) -> Vec<u8> {
+        assert!(blocks.len() % BLOCK_SIZE == 0, "not block aligned");
+        blocks
+            .chunks(BLOCK_SIZE)
+            .fold(state.to_vec(), |state, block| self.compress(&state, block))
+    }
+
+    pub fn digest(&self, msg: &[u8]) -> Vec<u8> {
+        self.iterate(&self.iv, &[msg, &padding(msg.len() as u64)].concat())
+    }
+
+    /// How many times the compression function has run.
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    pub fn reset_calls(&self) {
+        self.calls.store(0, Ordering::Relaxed);
+    }
+
+    /// Birthday search for two different single blocks that collide from
+    /// `state`. Returns both blocks and the state they lead to.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::md::MerkleDamgard;
+    /// let md = MerkleDamgard::toy(2);
+    /// let (b1, b2, next) = md.find_collision(md.iv());
+    /// assert_ne!(b1, b2);
+    /// assert_eq!(md.compress(md.iv(), &b1), next);
+    /// assert_eq!(md.compress(md.iv(), &b2), next);
+    /// ```
+    pub fn find_collision(&self, state: &[u8]) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let mut seen: HashMap<Vec<u8>, Vec<u8>> = HashMap::new();
+        loop {
+            let block = crate::utils::rand_bytes(BLOCK_SIZE);
+            let next = self.compress(state, &block);
+            match seen.get(&next) {
+                Some(other) if other != &block => return (other.clone(), block, next),
+                _ => {
+                    seen.insert(next, block);
+                }
+            }
+        }
+    }
+
+    /// Birthday search for a block from `s1` and a block from `s2` that lead
+    /// to the same state. Returns both blocks and that state.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::md::MerkleDamgard;
+    /// let md = MerkleDamgard::toy(2);
+    /// let other = md.iterate(md.iv(), &[0; 16]);
+    /// let (b1, b2, next) = md.collide(md.iv(), &other);
+    /// assert_eq!(md.compress(md.iv(), &b1), next);
+    /// assert_eq!(md.compress(&other, &b2), next);
+    /// ```
+    pub fn collide(&self, s1: &[u8], s2: &[u8]) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let states = [s1, s2];
+        let mut seen: [HashMap<Vec<u8>, Vec<u8>>; 2] = [HashMap::new(), HashMap::new()];
+        for side in [0, 1].into_iter().cycle() {
+            let block = crate::utils::rand_bytes(BLOCK_SIZE);
+            let next = self.compress(states[side], &block);
+            if let Some(other) = seen[1 - side].get(&next) {
+                if s1 != s2 || other != &block {
+                    let other = other.clone();
+                    return match side {
+                        0 => (block, other, next),
+                        _ => (other, block, next),
+                    };
+                }
+            }
+            seen[side].insert(next, block);
+        }
+        unreachable!()
+    }
+
+    /// Build a Kelsey-Schneier expandable message from `state`: a set of
+    /// messages of every length from `k` to `k + 2^k - 1` blocks, that all
+    /// end up in the same state.
+    ///
+    /// See challenge 53.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::md::MerkleDamgard;
+    /// let md = MerkleDamgard::toy(2);
+    /// let expandable = md.expandable(md.iv(), 4);
+    /// assert_eq!(expandable.lengths(), 4..=19);
+    /// for len in expandable.lengths() {
+    ///     let msg = expandable.message(len).unwrap();
+    ///     assert_eq!(msg.len(), len * 16);
+    ///     assert_eq!(md.iterate(md.iv(), &msg), expandable.state);
+    /// }
+    /// assert_eq!(expandable.message(20), None);
+    /// ```
+    pub fn expandable(&self, state: &[u8], k: usize) -> Expandable {
+        let dummy = [0; BLOCK_SIZE];
+        let mut state = state.to_vec();
+        let mut stages = vec![];
+
+        // Stage i collides a single block with 2^i dummy blocks plus one more
+        for i in (0..k).rev() {
+            let prefix = dummy.repeat(1 << i);
+            let (short, last, next) = self.collide(&state, &self.iterate(&state, &prefix));
+            stages.push((short, [prefix, last].concat()));
+            state = next;
+        }
+
+        Expandable { stages, state }
+    }
+
+    /// Build a diamond structure: 2^k random starting states, collided in
+    /// pairs, then the results collided in pairs, and so on down to a single
+    /// state. Each level's collisions are independent of each other, so they
+    /// get farmed out across threads.
+    ///
+    /// See challenge 54.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::md::MerkleDamgard;
+    /// let md = MerkleDamgard::toy(2);
+    /// let diamond = md.diamond(4);
+    /// assert_eq!(diamond.leaves().count(), 16);
+    /// for (i, leaf) in diamond.leaves().enumerate() {
+    ///     assert_eq!(md.iterate(leaf, &diamond.path(i)), diamond.state);
+    /// }
+    /// ```
+    pub fn diamond(&self, k: usize) -> Diamond {
+        assert!(k > 0, "a diamond needs at least one level");
+        let mut leaves = HashMap::new();
+        while leaves.len() < 1 << k {
+            let state = crate::utils::rand_bytes(self.state_size());
+            leaves.insert(state, ());
+        }
+        let mut states = leaves.into_keys().collect::<Vec<_>>();
+        let index = states
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (state.clone(), i))
+            .collect();
+
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let mut levels = vec![];
+        while states.len() > 1 {
+            let pairs = states.chunks(2).collect::<Vec<_>>();
+            let chunk_size = (pairs.len() + threads - 1) / threads;
+            let collisions = thread::scope(|scope| {
+                let handles = pairs
+                    .chunks(chunk_size)
+                    .map(|chunk| {
+                        scope.spawn(move || {
+                            chunk
+                                .iter()
+                                .map(|pair| self.collide(&pair[0], &pair[1]))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().unwrap())
+                    .collect::<Vec<_>>()
+            });
+
+            let mut level = vec![];
+            let mut next = vec![];
+            for (pair, (b1, b2, state)) in pairs.iter().zip(collisions) {
+                level.push((pair[0].clone(), b1));
+                level.push((pair[1].clone(), b2));
+                next.push(state);
+            }
+            levels.push(level);
+            states = next;
+        }
+
+        Diamond {
+            levels,
+            index,
+            state: states.pop().unwrap(),
+        }
+    }
+
+    /// Joux's multicollisions: `n` single-block collisions chained one after
+    /// the other give 2^n messages that all end up in the same state, for
+    /// about n times the cost of one.
+    ///
+    /// See challenge 52.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::md::{self, MerkleDamgard};
+    /// let md = MerkleDamgard::toy(2);
+    /// let stages = md.multicollision(md.iv(), 4);
+    /// let msgs = md::expand(&stages).collect::<Vec<_>>();
+    /// assert_eq!(msgs.len(), 16);
+    /// let digest = md.digest(&msgs[0]);
+    /// assert!(msgs.iter().all(|msg| md.digest(msg) == digest));
+    /// ```
+    pub fn multicollision(&self, state: &[u8], n: usize) -> Vec<Collision> {
+        let mut state = state.to_vec();
+        (0..n)
+            .map(|_| {
+                let (b1, b2, next) = self.find_collision(&state);
+                state = next.clone();
+                Collision {
+                    blocks: [b1, b2],
+                    state: next,
+                }
+            })
+            .collect()
+    }
+}
+
+/// One link in a multicollision: two blocks leading to the same state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Collision {
+    pub blocks: [Vec<u8>; 2],
+    pub state: Vec<u8>,
+}
+
+/// A family of colliding messages of different lengths.
+#[derive(Clone, Debug)]
+pub struct Expandable {
+    /// Pairs of one-block and 2^i + 1 block messages, from the largest i down.
+    pub stages: Vec<(Vec<u8>, Vec<u8>)>,
+    /// The state every message ends up in.
+    pub state: Vec<u8>,
+}
+
+impl Expandable {
+    /// The message lengths on offer, in blocks.
+    pub fn lengths(&self) -> RangeInclusive<usize> {
+        let k = self.stages.len();
+        k..=k + (1 << k) - 1
+    }
+
+    /// The message that's `blocks` blocks long, if there is one.
+    pub fn message(&self, blocks: usize) -> Option<Vec<u8>> {
+        if !self.lengths().contains(&blocks) {
+            return None;
+        }
+        // Each long message in stage i is 2^i blocks longer than the short
+        // one, so the extra length just picks them out bit by bit
+        let extra = blocks - self.stages.len();
+        let k = self.stages.len();
+        Some(
+            self.stages
+                .iter()
+                .enumerate()
+                .flat_map(|(n, (short, long))| match extra >> (k - 1 - n) & 1 {
+                    0 => short.clone(),
+                    _ => long.clone(),
+                })
+                .collect(),
+        )
+    }
+}
+
+/// A binary tree of collisions, funnelling 2^k states into one.
+#[derive(Clone, Debug)]
+pub struct Diamond {
+    /// For each level, every state along with the block that takes it (and
+    /// its sibling) to the next level down.
+    levels: Vec<Vec<(Vec<u8>, Vec<u8>)>>,
+    /// Which leaf each starting state is.
+    index: HashMap<Vec<u8>, usize>,
+    /// The state at the bottom of the diamond.
+    pub state: Vec<u8>,
+}
+
+impl Diamond {
+    pub fn leaves(&self) -> impl Iterator<Item = &[u8]> {
+        self.levels
+            .first()
+            .into_iter()
+            .flatten()
+            .map(|(state, _)| &state[..])
+    }
+
+    /// Which leaf `state` is, if it's one of them.
+    pub fn leaf(&self, state: &[u8]) -> Option<usize> {
+        self.index.get(state).copied()
+    }
+
+    /// The k blocks that lead from a leaf down to the final state.
+    pub fn path(&self, mut leaf: usize) -> Vec<u8> {
+        let mut path = vec![];
+        for level in &self.levels {
+            path.extend(&level[leaf].1);
+            leaf /= 2;
+        }
+        path
+    }
+}
+
+/// All 2^n messages from a chain of `n` collisions.
+pub fn expand(stages: &[Collision]) -> impl Iterator<Item = Vec<u8>> + '_ {
+    (0..1usize << stages.len()).map(move |choice| choose(stages, choice))
+}
+
+/// One of the messages from a chain of collisions, where bit `i` of `choice`
+/// picks the block for stage `i`.
+pub fn choose(stages: &[Collision], choice: usize) -> Vec<u8> {
+    stages
+        .iter()
+        .enumerate()
+        .flat_map(|(i, stage)| stage.blocks[choice >> i & 1].clone())
+        .collect()
+}
+
+/// MD strengthening: a 1 bit, zeros, and the message length in bits as a
+/// 64-bit big-endian integer, out to a whole number of blocks.
+///
+/// # Examples
+/// ```
+/// use cpr::md::padding;
+/// assert_eq!(padding(0).len(), 16);
+/// assert_eq!(padding(7).len(), 9);
+/// assert_eq!(padding(8).len(), 24);
+/// assert_eq!(&padding(16)[8..], &[0, 0, 0, 0, 0, 0, 0, 128]);
+/// ```
+pub fn padding(len: u64) -> Vec<u8> {
+    let mut pad = vec![0x80];
+    while (len as usize + pad.len() + 8) % BLOCK_SIZE != 0 {
+        pad.push(0);
+    }
+    pad.extend((len * 8).to_be_bytes());
+    pad
+}
@@ -0,0 +1,349 @@
+use crate::{
+    bigint::{self, BigUint},
+    dlog,
+    ec::{self, Point},
+};
+
+/// The challenge's Montgomery curve, v^2 = u^3 + 534u^2 + u, over the same
+/// field as (and equivalent to) [`ec::Curve::challenge`]. The base point u = 4
+/// maps onto the Weierstrass base point.
+pub const CHALLENGE_A: u64 = 534;
+pub const CHALLENGE_U: u64 = 4;
+
+/// How many points there are on the challenge curve: eight times the order of
+/// the base point.
+pub const CHALLENGE_ORDER: &str = "233970423115425145498902418297807005944";
+
+/// A Montgomery curve Bv^2 = u^3 + Au^2 + u over GF(p).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Curve {
+    pub p: BigUint,
+    pub a: BigUint,
+    pub b: BigUint,
+}
+
+/// Projective u-coordinate (U : W), standing for U/W. W = 0 is the point at
+/// infinity.
+type Projective = (BigUint, BigUint);
+
+impl Curve {
+    pub fn new(p: BigUint, a: BigUint, b: BigUint) -> Self {
+        Self { p, a, b }
+    }
+
+    pub fn challenge() -> Self {
+        Self::new(
+            ec::CHALLENGE_P.parse().unwrap(),
+            BigUint::from(CHALLENGE_A),
+            BigUint::one(),
+        )
+    }
+
+    /// Whether `u` belongs to a point on this curve, rather than on its
+    /// quadratic twist.
+    pub fn contains(&self, u: &BigUint) -> bool {
+        let u2 = self.mul_mod(u, u);
+        let rhs = &(&(&self.mul_mod(&u2, u) + &self.mul_mod(&self.a, &u2)) + u) % &self.p;
+        ec::sqrt_mod(&self.mul_mod(&rhs, &self.inv(&self.b)), &self.p).is_some()
+    }
+
+    /// Scalar multiplication using only u-coordinates (the Montgomery ladder).
+    /// The point at infinity comes out as 0. Since u doesn't see the sign of
+    /// v, this works just as well for points on the twist.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::{bigint::BigUint, ec::Group, montgomery::Curve};
+    /// let curve = Curve::challenge();
+    /// let group = Group::challenge();
+    /// let u = BigUint::from(cpr::montgomery::CHALLENGE_U);
+    /// assert!(curve.ladder(&u, &group.n).is_zero());
+    /// let k = BigUint::from(0xdeadbeefu64);
+    /// let want = group.curve.mul(&group.g, &k);
+    /// match curve.from_weierstrass(&want) {
+    ///     Some((u_k, _)) => assert_eq!(curve.ladder(&u, &k), u_k),
+    ///     None => unreachable!(),
+    /// }
+    /// ```
+    pub fn ladder(&self, u: &BigUint, k: &BigUint) -> BigUint {
+        let base: Projective = (u % &self.p, BigUint::one());
+        let mut low: Projective = (BigUint::one(), BigUint::zero());
+        let mut high = base.clone();
+        for i in (0..k.bits()).rev() {
+            // Keep high = low + base, stepping both up a bit at a time
+            if k.bit(i) {
+                low = self.xadd(&low, &high, &base);
+                high = self.xdbl(&high);
+            } else {
+                high = self.xadd(&low, &high, &base);
+                low = self.xdbl(&low);
+            }
+        }
+        self.to_affine(&low)
+    }
+
+    /// The equivalent short Weierstrass curve: a = (3 - A^2) / 3B^2 and
+    /// b = (2A^3 - 9A) / 27B^3.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::{ec, montgomery::Curve};
+    /// assert_eq!(Curve::challenge().weierstrass(), ec::Curve::challenge());
+    /// ```
+    pub fn weierstrass(&self) -> ec::Curve {
+        let a2 = self.mul_mod(&self.a, &self.a);
+        let b2 = self.mul_mod(&self.b, &self.b);
+        let a = self.mul_mod(
+            &self.sub_mod(&BigUint::from(3u64), &a2),
+            &self.inv(&(&b2 * 3)),
+        );
+        let b = self.mul_mod(
+            &self.sub_mod(&(&self.mul_mod(&a2, &self.a) * 2), &(&self.a * 9)),
+            &self.inv(&(&self.mul_mod(&b2, &self.b) * 27)),
+        );
+        ec::Curve::new(self.p.clone(), a, b)
+    }
+
+    /// Map (u, v) to (u/B + A/3B, v/B) on the Weierstrass curve.
+    pub fn to_weierstrass(&self, u: &BigUint, v: &BigUint) -> Point {
+        Point::Affine(self.weierstrass_x(u), self.mul_mod(v, &self.inv(&self.b)))
+    }
+
+    /// Map (x, y) back to (Bx - A/3, By), or nothing for the point at infinity
+    /// (which has no affine u-coordinate).
+    pub fn from_weierstrass(&self, point: &Point) -> Option<(BigUint, BigUint)> {
+        match point {
+            Point::Infinity => None,
+            Point::Affine(x, y) => {
+                let a_3 = self.mul_mod(&self.a, &self.inv(&BigUint::from(3u64)));
+                let u = self.sub_mod(&self.mul_mod(&self.b, x), &a_3);
+                Some((u, self.mul_mod(&self.b, y)))
+            }
+        }
+    }
+
+    fn weierstrass_x(&self, u: &BigUint) -> BigUint {
+        let num = &(u * 3) + &self.a;
+        self.mul_mod(&num, &self.inv(&(&self.b * 3)))
+    }
+
+    /// Double: U' = (U^2 - W^2)^2, W' = 4UW(U^2 + AUW + W^2).
+    fn xdbl(&self, (u, w): &Projective) -> Projective {
+        let (u2, w2, uw) = (self.mul_mod(u, u), self.mul_mod(w, w), self.mul_mod(u, w));
+        let t = self.sub_mod(&u2, &w2);
+        let s = &(&(&u2 + &self.mul_mod(&self.a, &uw)) + &w2) % &self.p;
+        (self.mul_mod(&t, &t), self.mul_mod(&(&uw * 4), &s))
+    }
+
+    /// Differential addition: given P, Q and P - Q, find P + Q.
+    fn xadd(
+        &self,
+        (u2, w2): &Projective,
+        (u3, w3): &Projective,
+        (ud, wd): &Projective,
+    ) -> Projective {
+        let s = self.sub_mod(&self.mul_mod(u2, u3), &self.mul_mod(w2, w3));
+        let t = self.sub_mod(&self.mul_mod(u2, w3), &self.mul_mod(w2, u3));
+        (
+            self.mul_mod(wd, &self.mul_mod(&s, &s)),
+            self.mul_mod(ud, &self.mul_mod(&t, &t)),
+        )
+    }
+
+    /// U/W, which conveniently comes out as 0 for the point at infinity.
+    fn to_affine(&self, (u, w): &Projective) -> BigUint {
+        self.mul_mod(u, &self.inv(w))
+    }
+
+    fn mul_mod(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        &(a * b) % &self.p
+    }
+
+    fn sub_mod(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        &(&(&(a % &self.p) + &self.p) - &(b % &self.p)) % &self.p
+    }
+
+    /// a^(p - 2), which is the inverse of a, or 0 if a is 0.
+    fn inv(&self, a: &BigUint) -> BigUint {
+        a.modpow(&(&self.p - 2), &self.p)
+    }
+}
+
+/// A curve along with a base point u, its order n, and how many points there
+/// are on the curve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Group {
+    pub curve: Curve,
+    pub u: BigUint,
+    pub n: BigUint,
+    pub order: BigUint,
+}
+
+impl Group {
+    pub fn new(curve: Curve, u: BigUint, n: BigUint, order: BigUint) -> Self {
+        Self { curve, u, n, order }
+    }
+
+    pub fn challenge() -> Self {
+        Self::new(
+            Curve::challenge(),
+            BigUint::from(CHALLENGE_U),
+            ec::CHALLENGE_N.parse().unwrap(),
+            CHALLENGE_ORDER.parse().unwrap(),
+        )
+    }
+
+    /// How many points there are on the quadratic twist: 2p + 2 minus the
+    /// number on the curve.
+    pub fn twist_order(&self) -> BigUint {
+        &(&(&self.curve.p * 2) + 2) - &self.order
+    }
+
+    pub fn keypair(&self) -> Keypair {
+        let private = BigUint::random_range(&BigUint::one(), &self.n);
+        let public = self.curve.ladder(&self.u, &private);
+        Keypair { private, public }
+    }
+}
+
+/// An x-only ECDH keypair.
+///
+/// See challenge 60.
+///
+/// # Examples
+/// ```
+/// use cpr::montgomery::Group;
+/// let group = Group::challenge();
+/// let alice = group.keypair();
+/// let bob = group.keypair();
+/// assert_eq!(
+///     alice.shared_secret(&group, &bob.public),
+///     bob.shared_secret(&group, &alice.public),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Keypair {
+    pub private: BigUint,
+    pub public: BigUint,
+}
+
+impl Keypair {
+    /// Note that this doesn't check that `other` is actually on the curve.
+    pub fn shared_secret(&self, group: &Group, other: &BigUint) -> BigUint {
+        group.curve.ladder(other, &self.private)
+    }
+}
+
+/// The twist attack: hand Bob u-coordinates of small order points on the
+/// quadratic twist (for each odd factor of its order below `bound`) and brute
+/// force his private key mod each factor, up to sign, from the MAC he sends
+/// back. Signs get sorted out two residues at a time, and the rest of the key
+/// is caught with a kangaroo on the Weierstrass curve.
+///
+/// The key only comes back up to sign (as x or n - x), since u can't tell xP
+/// and -xP apart; both give the same shared secrets.
+///
+/// See challenge 60.
+pub fn twist_attack(
+    group: &Group,
+    public: &BigUint,
+    bound: u64,
+    oracle: impl Fn(&BigUint) -> (Vec<u8>, Vec<u8>),
+) -> Option<BigUint> {
+    let curve = &group.curve;
+    let twist_order = group.twist_order();
+
+    // Bob's key is ±n mod m
+    let (mut n, mut m) = (BigUint::zero(), BigUint::one());
+    let mut factors = vec![];
+    for r in dlog::small_factors(&twist_order, bound) {
+        if r == 2 || (&twist_order % (r * r)).is_zero() {
+            continue;
+        }
+
+        let h = twist_point(curve, &twist_order, &[r]);
+        let (msg, t) = oracle(&h);
+        let b = residue(curve, &h, r, &msg, &t)?;
+        factors.push(r);
+        if m.is_one() {
+            (n, m) = (BigUint::from(b), BigUint::from(r));
+            continue;
+        }
+
+        // Of the four ways to combine ±n mod m with ±b mod r, two are the
+        // negatives of the other two, so one more query picks the right pair
+        let moduli = [m.clone(), BigUint::from(r)];
+        let c1 = bigint::crt(&[n.clone(), BigUint::from(b)], &moduli)?;
+        let c2 = bigint::crt(&[n.clone(), BigUint::from(r - b)], &moduli)?;
+        let h = twist_point(curve, &twist_order, &factors);
+        let (msg, t) = oracle(&h);
+        n = if dlog::mac(&curve.ladder(&h, &c1), &msg) == t {
+            c1
+        } else {
+            c2
+        };
+        m = &m * r;
+    }
+
+    // The key is ±n + k * m for some k in [0, n / m]. Lifting u to the
+    // Weierstrass curve picks a sign for v too, so try everything.
+    let weierstrass = curve.weierstrass();
+    let g = weierstrass.lift_x(&curve.weierstrass_x(&group.u))?;
+    let y = weierstrass.lift_x(&curve.weierstrass_x(public))?;
+    let g_m = weierstrass.mul(&g, &m);
+    let top = &group.n / &m;
+    for y in [y.clone(), weierstrass.neg(&y)] {
+        for s in [n.clone(), &(&m - &n) % &m] {
+            let target = weierstrass.add(&y, &weierstrass.neg(&weierstrass.mul(&g, &s)));
+            if let Some(k) = dlog::kangaroo_in(&weierstrass, &g_m, &target, &BigUint::zero(), &top)
+            {
+                return Some(&s + &(&k * &m));
+            }
+        }
+    }
+    None
+}
+
+/// The u-coordinate of a random point on the twist, whose order is the
+/// product of `factors` (each of which must divide the twist's order exactly
+/// once).
+fn twist_point(curve: &Curve, twist_order: &BigUint, factors: &[u64]) -> BigUint {
+    let m = factors.iter().fold(BigUint::one(), |acc, &r| &acc * r);
+    let cofactor = twist_order / &m;
+    loop {
+        let u = BigUint::random_below(&curve.p);
+        if curve.contains(&u) {
+            continue;
+        }
+        let h = curve.ladder(&u, &cofactor);
+        if factors
+            .iter()
+            .all(|&r| !curve.ladder(&h, &(&m / r)).is_zero())
+        {
+            return h;
+        }
+    }
+}
+
+/// Find b in [0, r/2] for which u(bh) is the shared secret behind MAC `t`,
+/// walking up the multiples of h with differential additions.
+fn residue(curve: &Curve, h: &BigUint, r: u64, msg: &[u8], t: &[u8]) -> Option<u64> {
+    let base: Projective = (h.clone(), BigUint::one());
+    let mut prev: Projective = (BigUint::one(), BigUint::zero());
+    let mut cur = base.clone();
+    if dlog::mac(&BigUint::zero(), msg) == t {
+        return Some(0);
+    }
+    for b in 1..=r / 2 {
+        if dlog::mac(&curve.to_affine(&cur), msg) == t {
+            return Some(b);
+        }
+        let next = if b == 1 {
+            curve.xdbl(&cur)
+        } else {
+            curve.xadd(&cur, &base, &prev)
+        };
+        (prev, cur) = (cur, next);
+    }
+    None
+}
@@ -0,0 +1,57 @@
+use crate::utils::PRNG;
+
+/// The RC4 stream cipher, as a keystream generator that plugs into
+/// [`crate::utils::prng_stream_cipher`]. Each call to `gen` yields one byte of
+/// keystream.
+///
+/// See challenge 56.
+///
+/// # Examples
+/// ```
+/// use cpr::{rc4::Rc4, utils};
+/// let ct = utils::prng_stream_cipher(b"Plaintext", &mut Rc4::new(b"Key"));
+/// assert_eq!(hex::encode(&ct), "bbf316e8d940af0ad3");
+/// let pt = utils::prng_stream_cipher(&ct, &mut Rc4::new(b"Key"));
+/// assert_eq!(pt, b"Plaintext");
+///
+/// // RFC 6229, 40-bit key, offset 0
+/// let keystream = Rc4::new(&[1, 2, 3, 4, 5]).keystream(16);
+/// assert_eq!(hex::encode(keystream), "b2396305f03dc027ccc3524a0a1118a8");
+/// ```
+#[derive(Clone)]
+pub struct Rc4 {
+    s: [u8; 256],
+    i: u8,
+    j: u8,
+}
+
+impl Rc4 {
+    /// Run the key schedule. Keys can be anywhere from 1 to 256 bytes long.
+    pub fn new(key: &[u8]) -> Self {
+        assert!(!key.is_empty() && key.len() <= 256, "bad RC4 key length");
+        let mut s: [u8; 256] = std::array::from_fn(|i| i as u8);
+        let mut j = 0u8;
+        for (i, k) in (0..256).zip(key.iter().cycle()) {
+            j = j.wrapping_add(s[i]).wrapping_add(*k);
+            s.swap(i, j as usize);
+        }
+        Self { s, i: 0, j: 0 }
+    }
+
+    pub fn next_byte(&mut self) -> u8 {
+        self.i = self.i.wrapping_add(1);
+        self.j = self.j.wrapping_add(self.s[self.i as usize]);
+        self.s.swap(self.i as usize, self.j as usize);
+        self.s[self.s[self.i as usize].wrapping_add(self.s[self.j as usize]) as usize]
+    }
+
+    pub fn keystream(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next_byte()).collect()
+    }
+}
+
+impl PRNG for Rc4 {
+    fn gen(&mut self) -> u32 {
+        self.next_byte() as u32
+    }
+}
@@ -0,0 +1,450 @@
+use crate::{
+    bigint::BigUint,
+    hash::{sha1::Sha1, sha256::Sha256, Hash},
+};
+use rand::Rng;
+use std::cell::Cell;
+
+/// Primes below 2000, for cheap trial division before Miller-Rabin.
+fn small_primes() -> Vec<u64> {
+    let limit = 2000;
+    let mut sieve = vec![true; limit];
+    let mut primes = vec![];
+    for i in 2..limit {
+        if sieve[i] {
+            primes.push(i as u64);
+            for j in (i * i..limit).step_by(i) {
+                sieve[j] = false;
+            }
+        }
+    }
+    primes
+}
+
+/// Miller-Rabin primality test with `rounds` random bases.
+///
+/// # Examples
+/// ```
+/// use cpr::{bigint::BigUint, rsa::is_probable_prime};
+/// assert!(is_probable_prime(&BigUint::from(2u64), 20));
+/// assert!(is_probable_prime(&BigUint::from(7919u64), 20));
+/// assert!(!is_probable_prime(&BigUint::from(7917u64), 20));
+/// // Carmichael number
+/// assert!(!is_probable_prime(&BigUint::from(561u64), 20));
+/// // 2^127 - 1 is a Mersenne prime, 2^128 + 1 is not a prime
+/// assert!(is_probable_prime(&(&(BigUint::one() << 127) - 1), 20));
+/// assert!(!is_probable_prime(&(&(BigUint::one() << 128) + 1), 20));
+/// ```
+pub fn is_probable_prime(n: &BigUint, rounds: usize) -> bool {
+    if n < &BigUint::from(2u64) {
+        return false;
+    }
+    for p in small_primes() {
+        if n == &BigUint::from(p) {
+            return true;
+        }
+        if (n % p).is_zero() {
+            return false;
+        }
+    }
+
+    // Write n - 1 = d * 2^s
+    let n_minus_one = n - 1;
+    let s = n_minus_one.trailing_zeros();
+    let d = &n_minus_one >> s;
+    let two = BigUint::from(2u64);
+
+    'witness: for _ in 0..rounds {
+        let a = BigUint::random_range(&two, &n_minus_one);
+        let mut x = a.modpow(&d, n);
+        if x.is_one() || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&two, n);
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Generate a random prime of exactly `bits` bits. The top two bits are
+/// always set, so that the product of two of them has exactly twice as many.
+pub fn gen_prime(bits: usize) -> BigUint {
+    assert!(bits >= 3, "too few bits for a prime");
+    let top = &(BigUint::one() << (bits - 1)) + &(BigUint::one() << (bits - 2));
+    loop {
+        let mut candidate = &BigUint::random_bits(bits - 2) + &top;
+        if candidate.is_even() {
+            candidate = &candidate + 1;
+        }
+        if is_probable_prime(&candidate, 40) {
+            return candidate;
+        }
+    }
+}
+
+/// A hash with an ASN.1 DigestInfo encoding, for PKCS#1 v1.5 signatures.
+pub trait DigestInfo: Hash {
+    /// The DER-encoded DigestInfo up to (but not including) the digest itself.
+    const PREFIX: &'static [u8];
+
+    /// The full DigestInfo for `msg`.
+    fn digest_info(msg: &[u8]) -> Vec<u8> {
+        [Self::PREFIX, &Self::digest(msg)].concat()
+    }
+}
+
+impl DigestInfo for Sha1 {
+    const PREFIX: &'static [u8] = &[
+        0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+    ];
+}
+
+impl DigestInfo for Sha256 {
+    const PREFIX: &'static [u8] = &[
+        0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01,
+        0x05, 0x00, 0x04, 0x20,
+    ];
+}
+
+/// EMSA-PKCS1-v1_5 encoding: `00 01 FF .. FF 00 DigestInfo`, `len` bytes long.
+///
+/// # Examples
+/// ```
+/// use cpr::{hash::sha1::Sha1, rsa};
+/// let em = rsa::pkcs1_v15_sign_pad::<Sha1>(b"hi mom", 128).unwrap();
+/// assert_eq!(em.len(), 128);
+/// assert_eq!(&em[..3], &[0x00, 0x01, 0xff]);
+/// assert_eq!(rsa::pkcs1_v15_sign_pad::<Sha1>(b"hi mom", 40), None);
+/// ```
+pub fn pkcs1_v15_sign_pad<H: DigestInfo>(msg: &[u8], len: usize) -> Option<Vec<u8>> {
+    let info = H::digest_info(msg);
+    // At least 8 bytes of FF padding, as per RFC 8017
+    let pad_len = len.checked_sub(info.len() + 3).filter(|&n| n >= 8)?;
+    Some([&[0x00, 0x01], &vec![0xff; pad_len][..], &[0x00], &info].concat())
+}
+
+/// EME-PKCS1-v1_5 encoding: `00 02 PS 00 msg`, `len` bytes long, where PS is
+/// at least 8 random non-zero bytes.
+///
+/// # Examples
+/// ```
+/// use cpr::rsa;
+/// let em = rsa::pkcs1_v15_pad(b"kick it, CC", 32).unwrap();
+/// assert_eq!(em.len(), 32);
+/// assert_eq!(&em[..2], &[0x00, 0x02]);
+/// assert_eq!(rsa::pkcs1_v15_unpad(&em), Some(b"kick it, CC".to_vec()));
+/// assert_eq!(rsa::pkcs1_v15_pad(b"kick it, CC", 16), None);
+/// ```
+pub fn pkcs1_v15_pad(msg: &[u8], len: usize) -> Option<Vec<u8>> {
+    let pad_len = len.checked_sub(msg.len() + 3).filter(|&n| n >= 8)?;
+    let mut rng = rand::thread_rng();
+    let padding = (0..pad_len)
+        .map(|_| rng.gen_range(1..=255u8))
+        .collect::<Vec<_>>();
+    Some([&[0x00, 0x02], &padding[..], &[0x00], msg].concat())
+}
+
+/// Strip EME-PKCS1-v1_5 padding, if it's valid.
+pub fn pkcs1_v15_unpad(em: &[u8]) -> Option<Vec<u8>> {
+    let rest = em.strip_prefix(&[0x00, 0x02])?;
+    let end = rest.iter().position(|&b| b == 0x00).filter(|&n| n >= 8)?;
+    Some(rest[end + 1..].to_vec())
+}
+
+/// RSA public key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey {
+    pub n: BigUint,
+    pub e: BigUint,
+}
+
+impl PublicKey {
+    /// Textbook (unpadded) encryption, m^e mod n.
+    pub fn encrypt(&self, m: &BigUint) -> BigUint {
+        m.modpow(&self.e, &self.n)
+    }
+
+    /// Size of the modulus in bytes.
+    pub fn size(&self) -> usize {
+        (self.n.bits() + 7) / 8
+    }
+
+    /// Verify a PKCS#1 v1.5 signature by re-encoding the expected block and
+    /// comparing the whole thing.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::{hash::{sha1::Sha1, sha256::Sha256}, rsa};
+    /// let (public, private) = rsa::keygen(1024, 65537);
+    /// let sig = private.sign::<Sha256>(b"hi mom").unwrap();
+    /// assert_eq!(sig.len(), public.size());
+    /// assert!(public.verify::<Sha256>(b"hi mom", &sig));
+    /// assert!(!public.verify::<Sha256>(b"hi dad", &sig));
+    /// assert!(!public.verify::<Sha1>(b"hi mom", &sig));
+    /// ```
+    pub fn verify<H: DigestInfo>(&self, msg: &[u8], sig: &[u8]) -> bool {
+        let s = BigUint::from_bytes_be(sig);
+        if sig.len() != self.size() || s >= self.n {
+            return false;
+        }
+        let em = self.encrypt(&s).to_bytes_be_padded(self.size());
+        pkcs1_v15_sign_pad::<H>(msg, self.size()).map_or(false, |want| em == want)
+    }
+
+    /// Verify a PKCS#1 v1.5 signature the wrong way: parse the block from the
+    /// left, skip over however much padding there is, and stop reading as soon
+    /// as the hash matches. Anything after the hash is ignored.
+    ///
+    /// See challenge 42.
+    ///
+    /// # Examples
+    /// ```
+    /// use cpr::{hash::sha1::Sha1, rsa};
+    /// let (public, private) = rsa::keygen(1024, 3);
+    /// let sig = private.sign::<Sha1>(b"hi mom").unwrap();
+    /// assert!(public.verify_sloppy::<Sha1>(b"hi mom", &sig));
+    /// assert!(!public.verify_sloppy::<Sha1>(b"hi dad", &sig));
+    /// ```
+    pub fn verify_sloppy<H: DigestInfo>(&self, msg: &[u8], sig: &[u8]) -> bool {
+        let em = self
+            .encrypt(&BigUint::from_bytes_be(sig))
+            .to_bytes_be_padded(self.size());
+        let rest = match em.strip_prefix(&[0x00, 0x01]) {
+            Some(rest) => rest,
+            None => return false,
+        };
+        let padding = rest.iter().take_while(|&&b| b == 0xff).count();
+        match rest[padding..].strip_prefix(&[0x00]) {
+            Some(rest) => rest.starts_with(&H::digest_info(msg)),
+            None => false,
+        }
+    }
+}
+
+/// RSA private key, with the extra values needed for CRT decryption.
+#[derive(Clone, Debug)]
+pub struct PrivateKey {
+    pub n: BigUint,
+    pub e: BigUint,
+    pub d: BigUint,
+    p: BigUint,
+    q: BigUint,
+    dp: BigUint,
+    dq: BigUint,
+    q_inv: BigUint,
+}
+
+impl PrivateKey {
+    fn new(p: BigUint, q: BigUint, e: BigUint) -> Option<Self> {
+        let one = BigUint::one();
+        let n = &p * &q;
+        let phi = &(&p - &one) * &(&q - &one);
+        let d = e.modinv(&phi)?;
+        Some(Self {
+            dp: &d % &(&p - &one),
+            dq: &d % &(&q - &one),
+            q_inv: q.modinv(&p)?,
+            n,
+            e,
+            d,
+            p,
+            q,
+        })
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey {
+            n: self.n.clone(),
+            e: self.e.clone(),
+        }
+    }
+
+    /// Textbook decryption using the CRT (Garner's formula), which does two
+    /// half-size exponentiations instead of one full-size one.
+    pub fn decrypt(&self, c: &BigUint) -> BigUint {
+        let m1 = c.modpow(&self.dp, &self.p);
+        let m2 = c.modpow(&self.dq, &self.q);
+        let h = &(&self.q_inv * &(&(&m1 + &self.p) - &(&m2 % &self.p))) % &self.p;
+        &m2 + &(&h * &self.q)
+    }
+
+    /// Plain c^d mod n, for comparison.
+    pub fn decrypt_slow(&self, c: &BigUint) -> BigUint {
+        c.modpow(&self.d, &self.n)
+    }
+
+    /// PKCS#1 v1.5 signature over the `H` digest of `msg`. Fails if the
+    /// modulus is too small to fit the DigestInfo.
+    pub fn sign<H: DigestInfo>(&self, msg: &[u8]) -> Option<Vec<u8>> {
+        let size = self.public_key().size();
+        let em = pkcs1_v15_sign_pad::<H>(msg, size)?;
+        Some(
+            self.decrypt(&BigUint::from_bytes_be(&em))
+                .to_bytes_be_padded(size),
+        )
+    }
+}
+
+/// Generate a keypair with a `bits`-bit modulus and public exponent `e`.
+///
+/// See challenge 39.
+///
+/// # Examples
+/// ```
+/// use cpr::{bigint::BigUint, rsa};
+/// let (public, private) = rsa::keygen(512, 3);
+/// assert_eq!(public.n.bits(), 512);
+/// let m = BigUint::from_bytes_be(b"YELLOW SUBMARINE");
+/// let c = public.encrypt(&m);
+/// assert_eq!(private.decrypt(&c), m);
+/// assert_eq!(private.decrypt_slow(&c), m);
+/// ```
+pub fn keygen(bits: usize, e: u64) -> (PublicKey, PrivateKey) {
+    let e = BigUint::from(e);
+    loop {
+        let p = gen_prime(bits / 2);
+        let q = gen_prime(bits - bits / 2);
+        if p == q {
+            continue;
+        }
+        // Fails if e isn't coprime with (p - 1)(q - 1); just try again
+        if let Some(private) = PrivateKey::new(p, q, e.clone()) {
+            return (private.public_key(), private);
+        }
+    }
+}
+
+/// ceil(x / y)
+fn ceil_div(x: &BigUint, y: &BigUint) -> BigUint {
+    (&(x + y) - 1) / y
+}
+
+/// Bleichenbacher's attack on PKCS#1 v1.5 encryption padding: recover the
+/// plaintext of `c` with an oracle that only says whether a ciphertext
+/// decrypts to something starting with `00 02`. Returns the (padded)
+/// plaintext and how many oracle queries it took.
+///
+/// See challenges 47 and 48, and "Chosen Ciphertext Attacks Against Protocols
+/// Based on the RSA Encryption Standard PKCS #1" (Bleichenbacher, 1998).
+pub fn bleichenbacher(
+    c: &BigUint,
+    public: &PublicKey,
+    oracle: impl Fn(&BigUint) -> bool,
+) -> Option<(BigUint, usize)> {
+    let n = &public.n;
+    let queries = Cell::new(0);
+    let conforming = |s: &BigUint, c: &BigUint| {
+        queries.set(queries.get() + 1);
+        oracle(&(&(&public.encrypt(s) * c) % n))
+    };
+
+    let b = BigUint::one() << (8 * (public.size() - 2));
+    let (b2, b3) = (&b * 2, &b * 3);
+
+    // Step 1: blinding. Our ciphertext is most likely conforming already, in
+    // which case s0 = 1.
+    let one = BigUint::one();
+    let s0 = if conforming(&one, c) {
+        one
+    } else {
+        loop {
+            let s0 = BigUint::random_range(&2u64.into(), n);
+            if conforming(&s0, c) {
+                break s0;
+            }
+        }
+    };
+    let c0 = &(&public.encrypt(&s0) * c) % n;
+
+    let mut intervals = vec![(b2.clone(), &b3 - 1)];
+    let mut s = ceil_div(n, &b3);
+    for i in 1.. {
+        s = if i == 1 {
+            // Step 2a: the smallest s >= n / 3B that's conforming
+            while !conforming(&s, &c0) {
+                s = &s + 1;
+            }
+            s
+        } else if intervals.len() > 1 {
+            // Step 2b: more than one interval left, so just keep searching
+            s = &s + 1;
+            while !conforming(&s, &c0) {
+                s = &s + 1;
+            }
+            s
+        } else {
+            // Step 2c: one interval left, so pick s to roughly halve it
+            let (lo, hi) = &intervals[0];
+            let mut r = ceil_div(&(&(&(hi * &s) - &b2) * 2), n);
+            'search: loop {
+                let rn = &r * n;
+                let mut s = ceil_div(&(&b2 + &rn), hi);
+                let end = ceil_div(&(&b3 + &rn), lo);
+                while s < end {
+                    if conforming(&s, &c0) {
+                        break 'search s;
+                    }
+                    s = &s + 1;
+                }
+                r = &r + 1;
+            }
+        };
+
+        // Step 3: narrow the set of solutions
+        let mut narrowed: Vec<(BigUint, BigUint)> = vec![];
+        for (lo, hi) in &intervals {
+            let lo_s = lo * &s;
+            let mut r = if &lo_s + 1 > b3 {
+                ceil_div(&(&(&lo_s + 1) - &b3), n)
+            } else {
+                BigUint::zero()
+            };
+            let r_end = &(&(hi * &s) - &b2) / n;
+            while r <= r_end {
+                let rn = &r * n;
+                let new_lo = ceil_div(&(&b2 + &rn), &s).max(lo.clone());
+                let new_hi = (&(&(&b3 - 1) + &rn) / &s).min(hi.clone());
+                if new_lo <= new_hi {
+                    insert_interval(&mut narrowed, new_lo, new_hi);
+                }
+                r = &r + 1;
+            }
+        }
+        if narrowed.is_empty() {
+            return None;
+        }
+        intervals = narrowed;
+
+        // Step 4: done once we're down to a single value
+        if let [(lo, hi)] = &intervals[..] {
+            if lo == hi {
+                let m = &(lo * &s0.modinv(n)?) % n;
+                return Some((m, queries.get()));
+            }
+        }
+    }
+    None
+}
+
+/// Add [lo, hi] to a set of disjoint intervals, merging any it overlaps.
+fn insert_interval(intervals: &mut Vec<(BigUint, BigUint)>, mut lo: BigUint, mut hi: BigUint) {
+    intervals.retain(|(a, b)| {
+        if a <= &hi && &lo <= b {
+            if a < &lo {
+                lo = a.clone();
+            }
+            if b > &hi {
+                hi = b.clone();
+            }
+            false
+        } else {
+            true
+        }
+    });
+    intervals.push((lo, hi));
+}
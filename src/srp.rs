@@ -0,0 +1,421 @@
+use crate::{
+    bigint::BigUint,
+    dh,
+    hash::sha256::{self, Sha256},
+    mac::Hmac,
+    utils,
+};
+use std::{
+    collections::HashMap,
+    error::Error,
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+/// Group parameters shared by client and server.
+#[derive(Clone, Debug)]
+pub struct Params {
+    pub n: BigUint,
+    pub g: BigUint,
+    pub k: BigUint,
+}
+
+impl Params {
+    /// The NIST prime with g = 2 and k = 3, as in the challenge. (SRP-6a
+    /// proper would derive k as H(N || g).)
+    pub fn nist() -> Self {
+        let group = dh::Group::nist();
+        Self {
+            n: group.p,
+            g: group.g,
+            k: BigUint::from(3u64),
+        }
+    }
+}
+
+/// Which flavor of the protocol to speak.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// B = kv + g^b, u = H(A || B).
+    Srp,
+    /// B = g^b, u is a random 128-bit number sent along with B.
+    ///
+    /// See challenge 38.
+    Simplified,
+}
+
+/// Everything that goes over the wire, one message per line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Message {
+    Hello {
+        email: String,
+        a: BigUint,
+    },
+    Challenge {
+        salt: Vec<u8>,
+        b: BigUint,
+        u: Option<BigUint>,
+    },
+    Proof(Vec<u8>),
+    Verdict(bool),
+}
+
+impl Message {
+    fn to_line(&self) -> String {
+        match self {
+            // Everything binary gets a 0x prefix so that empty fields survive
+            Message::Hello { email, a } => format!("HELLO {} {:#x}", email, a),
+            Message::Challenge { salt, b, u } => match u {
+                Some(u) => format!("CHALLENGE 0x{} {:#x} {:#x}", hex::encode(salt), b, u),
+                None => format!("CHALLENGE 0x{} {:#x}", hex::encode(salt), b),
+            },
+            Message::Proof(mac) => format!("PROOF 0x{}", hex::encode(mac)),
+            Message::Verdict(true) => "OK".into(),
+            Message::Verdict(false) => "FAIL".into(),
+        }
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let bytes = |s: &str| hex::decode(s.strip_prefix("0x")?).ok();
+        let int = |s: &str| BigUint::from_hex(s.strip_prefix("0x")?);
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+        match parts[..] {
+            ["HELLO", email, a] => Some(Message::Hello {
+                email: email.into(),
+                a: int(a)?,
+            }),
+            ["CHALLENGE", salt, b] => Some(Message::Challenge {
+                salt: bytes(salt)?,
+                b: int(b)?,
+                u: None,
+            }),
+            ["CHALLENGE", salt, b, u] => Some(Message::Challenge {
+                salt: bytes(salt)?,
+                b: int(b)?,
+                u: Some(int(u)?),
+            }),
+            ["PROOF", mac] => Some(Message::Proof(bytes(mac)?)),
+            ["OK"] => Some(Message::Verdict(true)),
+            ["FAIL"] => Some(Message::Verdict(false)),
+            _ => None,
+        }
+    }
+}
+
+/// A line-oriented message stream over TCP.
+pub struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Connection {
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        })
+    }
+
+    pub fn connect(addr: SocketAddr) -> io::Result<Self> {
+        Self::new(TcpStream::connect(addr)?)
+    }
+
+    pub fn send(&mut self, msg: &Message) -> io::Result<()> {
+        writeln!(self.writer, "{}", msg.to_line())
+    }
+
+    pub fn recv(&mut self) -> Result<Message, Box<dyn Error>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err("connection closed".into());
+        }
+        Message::from_line(&line).ok_or_else(|| format!("bad message: {}", line.trim()).into())
+    }
+}
+
+/// x = H(salt || password), as an integer.
+pub fn private_key(salt: &[u8], password: &[u8]) -> BigUint {
+    BigUint::from_bytes_be(&sha256::digest(&[salt, password].concat()))
+}
+
+/// K = H(S).
+pub fn session_key(s: &BigUint) -> Vec<u8> {
+    sha256::digest(&s.to_bytes_be()).to_vec()
+}
+
+/// The final proof of knowledge: HMAC-SHA256(K, salt).
+pub fn proof(key: &[u8], salt: &[u8]) -> Vec<u8> {
+    Hmac::<Sha256>::new(key).sign(salt)
+}
+
+/// u = H(A || B), as an integer.
+fn scrambler(a: &BigUint, b: &BigUint) -> BigUint {
+    BigUint::from_bytes_be(&sha256::digest(
+        &[a.to_bytes_be(), b.to_bytes_be()].concat(),
+    ))
+}
+
+/// What the server keeps on file for each user.
+#[derive(Clone, Debug)]
+struct Verifier {
+    salt: Vec<u8>,
+    v: BigUint,
+}
+
+/// Server side of the protocol.
+///
+/// See challenge 36.
+#[derive(Clone, Debug)]
+pub struct Server {
+    params: Params,
+    variant: Variant,
+    users: HashMap<String, Verifier>,
+}
+
+/// A server that has sent its challenge and is waiting for the client's proof.
+pub struct ServerSession {
+    key: Vec<u8>,
+    salt: Vec<u8>,
+}
+
+impl Server {
+    pub fn new(params: Params, variant: Variant) -> Self {
+        Self {
+            params,
+            variant,
+            users: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, email: &str, password: &[u8]) {
+        let salt = utils::rand_bytes(16);
+        let x = private_key(&salt, password);
+        let v = self.params.g.modpow(&x, &self.params.n);
+        self.users.insert(email.into(), Verifier { salt, v });
+    }
+
+    /// Handle a client's hello.
+    pub fn challenge(&self, hello: &Message) -> Option<(ServerSession, Message)> {
+        let Message::Hello { email, a } = hello else {
+            return None;
+        };
+        let Verifier { salt, v } = self.users.get(email)?;
+        let Params { n, g, k } = &self.params;
+
+        let b = BigUint::random_below(n);
+        let (pub_b, u) = match self.variant {
+            Variant::Srp => {
+                let pub_b = &(&(k * v) + &g.modpow(&b, n)) % n;
+                let u = scrambler(a, &pub_b);
+                (pub_b, u)
+            }
+            Variant::Simplified => (g.modpow(&b, n), BigUint::random_bits(128)),
+        };
+
+        // S = (A * v^u)^b
+        let s = (&(a * &v.modpow(&u, n)) % n).modpow(&b, n);
+        let session = ServerSession {
+            key: session_key(&s),
+            salt: salt.clone(),
+        };
+        let msg = Message::Challenge {
+            salt: salt.clone(),
+            b: pub_b,
+            u: (self.variant == Variant::Simplified).then_some(u),
+        };
+        Some((session, msg))
+    }
+}
+
+impl ServerSession {
+    pub fn verify(&self, msg: &Message) -> bool {
+        match msg {
+            Message::Proof(mac) => Hmac::<Sha256>::new(&self.key).verify(&self.salt, mac),
+            _ => false,
+        }
+    }
+}
+
+/// Client side of the protocol.
+///
+/// See challenge 36.
+///
+/// # Examples
+/// ```
+/// use cpr::srp::{Client, Params, Server, Variant};
+/// for variant in [Variant::Srp, Variant::Simplified] {
+///     let mut server = Server::new(Params::nist(), variant);
+///     server.register("alice@example.com", b"hunter2");
+///
+///     let client = Client::new(Params::nist(), variant, "alice@example.com", b"hunter2");
+///     let (session, hello) = client.hello();
+///     let (server_session, challenge) = server.challenge(&hello).unwrap();
+///     assert!(server_session.verify(&session.proof(&challenge).unwrap()));
+///
+///     let client = Client::new(Params::nist(), variant, "alice@example.com", b"hunter3");
+///     let (session, hello) = client.hello();
+///     let (server_session, challenge) = server.challenge(&hello).unwrap();
+///     assert!(!server_session.verify(&session.proof(&challenge).unwrap()));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Client {
+    params: Params,
+    variant: Variant,
+    email: String,
+    password: Vec<u8>,
+}
+
+/// A client that has said hello and is waiting for the server's challenge.
+pub struct ClientSession {
+    client: Client,
+    a: BigUint,
+    pub_a: BigUint,
+}
+
+impl Client {
+    pub fn new(params: Params, variant: Variant, email: &str, password: &[u8]) -> Self {
+        Self {
+            params,
+            variant,
+            email: email.into(),
+            password: password.to_vec(),
+        }
+    }
+
+    pub fn hello(&self) -> (ClientSession, Message) {
+        let a = BigUint::random_below(&self.params.n);
+        let pub_a = self.params.g.modpow(&a, &self.params.n);
+        let msg = Message::Hello {
+            email: self.email.clone(),
+            a: pub_a.clone(),
+        };
+        let session = ClientSession {
+            client: self.clone(),
+            a,
+            pub_a,
+        };
+        (session, msg)
+    }
+}
+
+impl ClientSession {
+    /// Handle the server's challenge and produce a proof.
+    pub fn proof(self, challenge: &Message) -> Option<Message> {
+        let Message::Challenge { salt, b, u } = challenge else {
+            return None;
+        };
+        let Params { n, g, k } = &self.client.params;
+        let x = private_key(salt, &self.client.password);
+
+        let s = match (self.client.variant, u) {
+            (Variant::Srp, None) => {
+                // S = (B - k * g^x)^(a + u * x)
+                let u = scrambler(&self.pub_a, b);
+                let kgx = &(k * &g.modpow(&x, n)) % n;
+                let base = &(&(b + n) - &kgx) % n;
+                base.modpow(&(&self.a + &(&u * &x)), n)
+            }
+            (Variant::Simplified, Some(u)) => {
+                // S = B^(a + u * x)
+                b.modpow(&(&self.a + &(u * &x)), n)
+            }
+            _ => return None,
+        };
+
+        Some(Message::Proof(proof(&session_key(&s), salt)))
+    }
+}
+
+/// Serve logins over TCP on the loopback interface.
+pub struct Listener {
+    addr: SocketAddr,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Listener {
+    /// Start serving. Pass port 0 to let the OS pick an ephemeral port.
+    pub fn start(server: Server, port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let addr = listener.local_addr()?;
+        let running = Arc::new(AtomicBool::new(true));
+
+        let flag = running.clone();
+        let handle = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if !flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let _ = handle(stream, &server);
+                }
+            }
+        });
+
+        Ok(Self {
+            addr,
+            running,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        // Poke the listener so the accept loop notices that we're done
+        let _ = TcpStream::connect(self.addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn handle(stream: TcpStream, server: &Server) -> Result<(), Box<dyn Error>> {
+    let mut conn = Connection::new(stream)?;
+    let hello = conn.recv()?;
+    let Some((session, challenge)) = server.challenge(&hello) else {
+        return Ok(conn.send(&Message::Verdict(false))?);
+    };
+    conn.send(&challenge)?;
+    let proof = conn.recv()?;
+    conn.send(&Message::Verdict(session.verify(&proof)))?;
+    Ok(())
+}
+
+/// Log in to a server over TCP.
+///
+/// # Examples
+/// ```
+/// use cpr::srp::{login, Client, Listener, Params, Server, Variant};
+/// let mut server = Server::new(Params::nist(), Variant::Srp);
+/// server.register("alice@example.com", b"hunter2");
+/// let listener = Listener::start(server, 0).unwrap();
+///
+/// let client = Client::new(Params::nist(), Variant::Srp, "alice@example.com", b"hunter2");
+/// assert!(login(listener.addr(), &client).unwrap());
+/// let client = Client::new(Params::nist(), Variant::Srp, "alice@example.com", b"*******");
+/// assert!(!login(listener.addr(), &client).unwrap());
+/// ```
+pub fn login(addr: SocketAddr, client: &Client) -> Result<bool, Box<dyn Error>> {
+    let mut conn = Connection::connect(addr)?;
+    let (session, hello) = client.hello();
+    conn.send(&hello)?;
+    let challenge = conn.recv()?;
+    let proof = session.proof(&challenge).ok_or("unexpected challenge")?;
+    conn.send(&proof)?;
+    match conn.recv()? {
+        Message::Verdict(ok) => Ok(ok),
+        other => Err(format!("unexpected message: {:?}", other).into()),
+    }
+}
@@ -0,0 +1,325 @@
+use crate::{
+    hash::sha1::Sha1,
+    mac::{self, Hmac},
+};
+use std::{
+    collections::HashMap,
+    error::Error,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// A tiny HTTP server that checks HMAC-SHA1 signatures of file names with an
+/// artificially slow, early-exit comparison.
+///
+/// See challenges 31 and 32.
+///
+/// # Examples
+/// ```
+/// use cpr::{hash::sha1::Sha1, mac::Hmac, timing::{request, Server}};
+/// use std::time::Duration;
+/// let server = Server::start(b"YELLOW SUBMARINE", Duration::ZERO, 0).unwrap();
+/// let mac = Hmac::<Sha1>::new(b"YELLOW SUBMARINE").sign(b"foo");
+/// assert!(request(server.addr(), "foo", &mac).unwrap().0);
+/// assert!(!request(server.addr(), "bar", &mac).unwrap().0);
+/// ```
+pub struct Server {
+    addr: SocketAddr,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Server {
+    /// Start serving on the loopback interface. Pass port 0 to let the OS pick
+    /// an ephemeral port.
+    pub fn start(key: &[u8], delay: Duration, port: u16) -> io::Result<Self> {
+        Self::start_truncated(key, delay, port, usize::MAX)
+    }
+
+    /// Same as [`Server::start`], but only check the first `mac_len` bytes of
+    /// the HMAC. Handy for testing the attack without waiting all day.
+    pub fn start_truncated(
+        key: &[u8],
+        delay: Duration,
+        port: u16,
+        mac_len: usize,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let addr = listener.local_addr()?;
+        let running = Arc::new(AtomicBool::new(true));
+
+        let hmac = Hmac::<Sha1>::new(key);
+        let flag = running.clone();
+        let handle = thread::spawn(move || {
+            // Handle one connection at a time so that requests don't muddy
+            // each other's timings.
+            for stream in listener.incoming() {
+                if !flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let _ = handle(stream, &hmac, delay, mac_len);
+                }
+            }
+        });
+
+        Ok(Self {
+            addr,
+            running,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Block until the server thread exits (i.e. forever).
+    pub fn join(mut self) {
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        // Poke the listener so the accept loop notices that we're done
+        let _ = TcpStream::connect(self.addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn handle(
+    mut stream: TcpStream,
+    hmac: &Hmac<Sha1>,
+    delay: Duration,
+    mac_len: usize,
+) -> Result<(), Box<dyn Error>> {
+    let mut line = String::new();
+    BufReader::new(&mut stream).read_line(&mut line)?;
+
+    let status = match parse_request(&line) {
+        Some((file, signature)) => {
+            let mac = hmac.sign(file.as_bytes());
+            let mac = &mac[..mac_len.min(mac.len())];
+            if mac::insecure_compare(mac, &signature, delay) {
+                "200 OK"
+            } else {
+                "500 Internal Server Error"
+            }
+        }
+        None => "400 Bad Request",
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status
+    )?;
+    Ok(())
+}
+
+fn parse_request(line: &str) -> Option<(String, Vec<u8>)> {
+    let mut parts = line.split_whitespace();
+    if parts.next()? != "GET" {
+        return None;
+    }
+    let (path, query) = parts.next()?.split_once('?')?;
+    if path != "/test" {
+        return None;
+    }
+    let params = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .collect::<HashMap<_, _>>();
+    let file = params.get("file")?.to_string();
+    let signature = hex::decode(params.get("signature")?).ok()?;
+    Some((file, signature))
+}
+
+/// Ask the server to validate a signature. Returns whether it was accepted and
+/// how long the round trip took.
+pub fn request(addr: SocketAddr, file: &str, signature: &[u8]) -> io::Result<(bool, Duration)> {
+    let mut stream = TcpStream::connect(addr)?;
+    stream.set_nodelay(true)?;
+    let req = format!(
+        "GET /test?file={}&signature={} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        file,
+        hex::encode(signature)
+    );
+
+    let start = Instant::now();
+    stream.write_all(req.as_bytes())?;
+    let mut res = String::new();
+    stream.read_to_string(&mut res)?;
+    let elapsed = start.elapsed();
+
+    Ok((res.starts_with("HTTP/1.1 200"), elapsed))
+}
+
+/// How to boil a bunch of timing samples down into a single score.
+#[derive(Clone, Copy, Debug)]
+pub enum Statistic {
+    Median,
+    /// Mean after dropping this fraction of samples from each end.
+    TrimmedMean(f64),
+}
+
+impl Statistic {
+    pub fn score(&self, samples: &[Duration]) -> f64 {
+        let mut sorted = samples.iter().map(|d| d.as_secs_f64()).collect::<Vec<_>>();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        match *self {
+            Statistic::Median => median(&sorted),
+            Statistic::TrimmedMean(fraction) => {
+                let trim = (sorted.len() as f64 * fraction) as usize;
+                let kept = &sorted[trim..sorted.len() - trim];
+                kept.iter().sum::<f64>() / kept.len() as f64
+            }
+        }
+    }
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Knobs for the timing attack.
+#[derive(Clone, Copy, Debug)]
+pub struct AttackConfig {
+    /// Samples per candidate byte in the first pass.
+    pub samples: usize,
+    /// How many times to resample the leading candidates before giving up on
+    /// a byte and backtracking.
+    pub retries: usize,
+    /// How many of the leading candidates get resampled.
+    pub contenders: usize,
+    /// How far (in median absolute deviations) the winner has to stand out
+    /// from the runner-up.
+    pub confidence: f64,
+    pub statistic: Statistic,
+}
+
+impl Default for AttackConfig {
+    fn default() -> Self {
+        Self {
+            samples: 5,
+            retries: 8,
+            contenders: 8,
+            confidence: 4.,
+            statistic: Statistic::Median,
+        }
+    }
+}
+
+/// Recover the HMAC of `file` one byte at a time by timing the server's
+/// early-exit comparison.
+///
+/// See challenges 31 and 32.
+pub fn recover_mac(
+    addr: SocketAddr,
+    file: &str,
+    mac_len: usize,
+    config: &AttackConfig,
+) -> Option<Vec<u8>> {
+    let mut mac = vec![0u8; mac_len];
+    let mut i = 0;
+    let mut backtracks = 0;
+
+    while i < mac_len {
+        let found = if i == mac_len - 1 {
+            // No need to time the last byte, the server tells us outright
+            (0..=255u8).find(|&b| {
+                mac[i] = b;
+                matches!(request(addr, file, &mac), Ok((true, _)))
+            })
+        } else {
+            recover_byte(addr, file, &mut mac, i, config)
+        };
+
+        match found {
+            Some(b) => {
+                mac[i] = b;
+                i += 1;
+            }
+            None if i > 0 && backtracks < mac_len => {
+                // We probably picked a wrong byte earlier on. Back up.
+                backtracks += 1;
+                i -= 1;
+            }
+            None => return None,
+        }
+    }
+
+    Some(mac)
+}
+
+fn recover_byte(
+    addr: SocketAddr,
+    file: &str,
+    mac: &mut [u8],
+    i: usize,
+    config: &AttackConfig,
+) -> Option<u8> {
+    let mut samples: Vec<Vec<Duration>> = vec![vec![]; 256];
+    let mut candidates = (0..=255u8).collect::<Vec<_>>();
+    let mut leader = None;
+
+    for _ in 0..=config.retries {
+        for &b in &candidates {
+            mac[i] = b;
+            for _ in 0..config.samples {
+                let (_, elapsed) = request(addr, file, mac).ok()?;
+                samples[b as usize].push(elapsed);
+            }
+        }
+
+        let mut scores = (0..=255u8)
+            .map(|b| (b, config.statistic.score(&samples[b as usize])))
+            .collect::<Vec<_>>();
+        scores.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+        // Measure the spread of the field; the right byte should stick out
+        // well past the noise.
+        let center = median(&scores.iter().map(|(_, s)| *s).collect::<Vec<_>>());
+        let mut deviations = scores
+            .iter()
+            .map(|(_, s)| (s - center).abs())
+            .collect::<Vec<_>>();
+        deviations.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mad = median(&deviations);
+
+        // A single slow round trip can make any byte look like a winner, so
+        // only accept one that holds its lead after being resampled.
+        let (best, runner_up) = (scores[255], scores[254]);
+        if leader == Some(best.0) && best.1 - runner_up.1 > config.confidence * mad {
+            return Some(best.0);
+        }
+        leader = Some(best.0);
+
+        // Not sure yet: pile more samples onto the front runners
+        candidates = scores
+            .iter()
+            .rev()
+            .take(config.contenders)
+            .map(|(b, _)| *b)
+            .collect();
+    }
+
+    None
+}